@@ -0,0 +1,102 @@
+use crate::{types::DAMType, view::TimeManager};
+
+use super::{ChannelElement, DequeueError, Receiver};
+
+/// Wraps a [Receiver], folding every received element into an accumulator until a sentinel element arrives.
+/// This is useful for streaming reductions, such as summing each row of a matrix.
+pub struct AccumulatingReceiver<T: Clone, Acc, FType, SType>
+where
+    FType: Fn(Acc, T) -> Acc + Send + Sync,
+    SType: Fn(&T) -> bool + Send + Sync,
+{
+    receiver: Receiver<T>,
+    initial: Acc,
+    accumulator: Option<Acc>,
+    func: FType,
+    is_sentinel: SType,
+}
+
+impl<T: DAMType, Acc: Clone, FType, SType> AccumulatingReceiver<T, Acc, FType, SType>
+where
+    FType: Fn(Acc, T) -> Acc + Send + Sync,
+    SType: Fn(&T) -> bool + Send + Sync,
+{
+    /// Constructs a new accumulating receiver.
+    /// Elements for which `is_sentinel` returns true end the current accumulation, and are not folded in.
+    pub fn new(receiver: Receiver<T>, initial: Acc, func: FType, is_sentinel: SType) -> Self {
+        Self {
+            receiver,
+            accumulator: Some(initial.clone()),
+            initial,
+            func,
+            is_sentinel,
+        }
+    }
+
+    /// The underlying receiver.
+    pub fn receiver(&self) -> &Receiver<T> {
+        &self.receiver
+    }
+
+    /// Dequeues elements until a sentinel is found or the channel closes, and returns the accumulated value.
+    /// The accumulator is reset afterwards.
+    /// If the channel was closed without any elements arriving since the last flush, then this returns a DequeueError instead.
+    pub fn flush(&mut self, manager: &TimeManager) -> Result<Acc, DequeueError> {
+        let mut received = false;
+        loop {
+            match self.receiver.dequeue(manager) {
+                Ok(ChannelElement { time: _, data }) if (self.is_sentinel)(&data) => break,
+                Ok(ChannelElement { time: _, data }) => {
+                    let acc = self.accumulator.take().unwrap();
+                    self.accumulator = Some((self.func)(acc, data));
+                    received = true;
+                }
+                Err(DequeueError::Closed) if !received => return Err(DequeueError::Closed),
+                Err(DequeueError::Closed) => break,
+            }
+        }
+        Ok(self.accumulator.replace(self.initial.clone()).unwrap())
+    }
+
+    /// Discards the current accumulation, reinitializing it to the initial value.
+    pub fn reset(&mut self) {
+        self.accumulator = Some(self.initial.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AccumulatingReceiver;
+    use crate::{
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::{FunctionContext, GeneratorContext},
+    };
+
+    #[test]
+    fn test_accumulate_until_sentinel() {
+        let mut parent = ProgramBuilder::default();
+        let (snd, rcv) = parent.bounded(4);
+        parent.add_child(GeneratorContext::new(
+            || [1u32, 2, 3, 0, 4, 5, 0, 6].into_iter(),
+            snd,
+        ));
+
+        let mut consumer = FunctionContext::default();
+        rcv.attach_receiver(&consumer);
+        let mut accumulator = AccumulatingReceiver::new(rcv, 0u32, |acc, x| acc + x, |x| *x == 0);
+        consumer.set_run(move |time| {
+            assert_eq!(accumulator.flush(time).unwrap(), 6);
+            assert_eq!(accumulator.flush(time).unwrap(), 9);
+            // The final group is terminated by the channel closing instead of a sentinel.
+            assert_eq!(accumulator.flush(time).unwrap(), 6);
+            assert!(accumulator.flush(time).is_err());
+        });
+        parent.add_child(consumer);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
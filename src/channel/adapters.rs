@@ -2,9 +2,20 @@
 //! In particular, these are useful when some memory may contain elements of different types
 //! And so channels of different types may be connected to the memory.
 
-use crate::{context::Context, context_tools::DAMType, structures::TimeManager};
+use std::sync::Arc;
 
-use super::{ChannelElement, DequeueError, EnqueueError, PeekResult, Receiver, Sender};
+use crate::{
+    context::Context,
+    context_tools::DAMType,
+    shim::Mutex,
+    structures::{Time, TimeManager},
+};
+
+use super::{
+    handle::ChannelHandle, ChannelElement, ChannelID, ChannelObserver, CloseError, DequeueError,
+    EnqueueError, MonitorID, NamespacedChannelID, PeekResult, Receiver, SendOptions, Sender,
+    StallHistogram,
+};
 
 /// An adapter for Receivers, delegating and converting all underlying operations
 pub trait RecvAdapter<U> {
@@ -90,3 +101,576 @@ where
         Sender::attach_sender(self, ctx)
     }
 }
+
+impl<T: DAMType> RecvAdapter<T> for Arc<Mutex<Receiver<T>>> {
+    fn attach_receiver(&self, ctx: &dyn Context) {
+        self.lock().unwrap().attach_receiver(ctx)
+    }
+
+    fn peek(&self) -> PeekResult<T> {
+        self.lock().unwrap().peek()
+    }
+
+    fn peek_next(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        self.lock().unwrap().peek_next(manager)
+    }
+
+    fn dequeue(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        self.lock().unwrap().dequeue(manager)
+    }
+}
+
+/// A receiver which may be observed by multiple owners, such as a monitor alongside the actual consumer.
+/// Only one owner should dequeue from the channel; the others should use a [PeekHandle].
+#[derive(Clone)]
+pub struct SharedReceiver<T: Clone> {
+    underlying: Arc<Mutex<Receiver<T>>>,
+}
+
+impl<T: DAMType> SharedReceiver<T> {
+    /// Returns a handle which may only peek at the channel.
+    pub fn peek_only_handle(&self) -> PeekHandle<T> {
+        PeekHandle {
+            underlying: self.underlying.clone(),
+        }
+    }
+}
+
+impl<T: DAMType> RecvAdapter<T> for SharedReceiver<T> {
+    fn attach_receiver(&self, ctx: &dyn Context) {
+        self.underlying.attach_receiver(ctx)
+    }
+
+    fn peek(&self) -> PeekResult<T> {
+        self.underlying.peek()
+    }
+
+    fn peek_next(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        self.underlying.peek_next(manager)
+    }
+
+    fn dequeue(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        self.underlying.dequeue(manager)
+    }
+}
+
+/// A read-only view of a [SharedReceiver].
+#[derive(Clone)]
+pub struct PeekHandle<T: Clone> {
+    underlying: Arc<Mutex<Receiver<T>>>,
+}
+
+impl<T: DAMType> PeekHandle<T> {
+    /// See: [Receiver::peek]
+    pub fn peek(&self) -> PeekResult<T> {
+        self.underlying.lock().unwrap().peek()
+    }
+}
+
+impl<T: DAMType> Receiver<T> {
+    /// Converts the receiver into one which can be shared between multiple owners.
+    pub fn shared(self) -> SharedReceiver<T> {
+        SharedReceiver {
+            underlying: Arc::new(Mutex::new(self)),
+        }
+    }
+}
+
+impl<T: DAMType> SendAdapter<T> for Arc<Mutex<Sender<T>>> {
+    fn attach_sender(&self, ctx: &dyn Context) {
+        self.lock().unwrap().attach_sender(ctx)
+    }
+
+    fn enqueue(&self, manager: &TimeManager, data: ChannelElement<T>) -> Result<(), EnqueueError> {
+        self.lock().unwrap().enqueue(manager, data)
+    }
+
+    fn wait_until_available(&self, manager: &TimeManager) -> Result<(), EnqueueError> {
+        self.lock().unwrap().wait_until_available(manager)
+    }
+}
+
+/// A sender which may be cloned, such as for several worker threads of a single context sharing one output.
+/// Every clone sends through the same underlying sender, so flow control is tracked across all of them, and sends are serialized.
+/// The channel is closed once the last clone is dropped.
+#[derive(Clone)]
+pub struct SharedSender<T: Clone> {
+    underlying: Arc<Mutex<Sender<T>>>,
+}
+
+impl<T: DAMType> SharedSender<T> {
+    /// See: [Sender::id]
+    pub fn id(&self) -> ChannelID {
+        self.underlying.lock().unwrap().id()
+    }
+
+    /// See: [Sender::namespaced_id]
+    pub fn namespaced_id(&self) -> Option<NamespacedChannelID> {
+        self.underlying.lock().unwrap().namespaced_id()
+    }
+
+    /// See: [Sender::set_debug_label]
+    pub fn set_debug_label(&self, label: &str) {
+        self.underlying.lock().unwrap().set_debug_label(label)
+    }
+
+    /// See: [Sender::debug_label]
+    pub fn debug_label(&self) -> Option<Arc<str>> {
+        self.underlying.lock().unwrap().debug_label()
+    }
+
+    /// See: [Sender::add_observer]
+    pub fn add_observer(&self, observer: ChannelObserver) {
+        self.underlying.lock().unwrap().add_observer(observer)
+    }
+
+    /// See: [Sender::monitor_occupancy]
+    pub fn monitor_occupancy(
+        &self,
+        threshold: f64,
+        callback: Box<dyn Fn(f64) + Send>,
+    ) -> MonitorID {
+        self.underlying
+            .lock()
+            .unwrap()
+            .monitor_occupancy(threshold, callback)
+    }
+
+    /// See: [Sender::remove_monitor]
+    pub fn remove_monitor(&self, id: MonitorID) -> bool {
+        self.underlying.lock().unwrap().remove_monitor(id)
+    }
+
+    /// See: [Sender::annotate_with_context]
+    pub fn annotate_with_context(&self, sender: &dyn Context) {
+        self.underlying
+            .lock()
+            .unwrap()
+            .annotate_with_context(sender)
+    }
+
+    /// See: [Sender::enqueue_iter]. The lock is only held for one element at a time, so sends from other clones may interleave.
+    pub fn enqueue_iter(
+        &self,
+        manager: &TimeManager,
+        elements: impl IntoIterator<Item = ChannelElement<T>>,
+    ) -> Result<(), EnqueueError> {
+        elements
+            .into_iter()
+            .try_for_each(|element| self.enqueue(manager, element))
+    }
+
+    /// See: [Sender::is_void]
+    pub fn is_void(&self) -> bool {
+        self.underlying.lock().unwrap().is_void()
+    }
+
+    /// See: [Sender::enqueue_or_void]
+    pub fn enqueue_or_void(
+        &self,
+        manager: &TimeManager,
+        data: ChannelElement<T>,
+    ) -> Result<(), EnqueueError> {
+        self.underlying
+            .lock()
+            .unwrap()
+            .enqueue_or_void(manager, data)
+    }
+
+    /// See: [Sender::peek_remaining_capacity]. The capacity is shared between every clone.
+    pub fn peek_remaining_capacity(&self) -> usize {
+        self.underlying.lock().unwrap().peek_remaining_capacity()
+    }
+
+    /// See: [Sender::stall_histogram]
+    pub fn stall_histogram(&self) -> StallHistogram {
+        self.underlying.lock().unwrap().stall_histogram()
+    }
+
+    /// See: [Sender::is_closed]
+    pub fn is_closed(&self) -> bool {
+        self.underlying.lock().unwrap().is_closed()
+    }
+
+    /// See: [Sender::close]. This closes the channel for every clone.
+    pub fn close(&self) {
+        self.underlying.lock().unwrap().close()
+    }
+
+    /// See: [Sender::close_on_tick]
+    pub fn close_on_tick(&self, tick: Time) {
+        self.underlying.lock().unwrap().close_on_tick(tick)
+    }
+
+    /// See: [Sender::close_gracefully]. Other clones block until the channel has drained.
+    pub fn close_gracefully(&self, manager: &TimeManager, timeout: u64) -> Result<(), CloseError> {
+        self.underlying
+            .lock()
+            .unwrap()
+            .close_gracefully(manager, timeout)
+    }
+}
+
+impl<T: DAMType> SendAdapter<T> for SharedSender<T> {
+    fn attach_sender(&self, ctx: &dyn Context) {
+        self.underlying.attach_sender(ctx)
+    }
+
+    fn enqueue(&self, manager: &TimeManager, data: ChannelElement<T>) -> Result<(), EnqueueError> {
+        self.underlying.enqueue(manager, data)
+    }
+
+    fn wait_until_available(&self, manager: &TimeManager) -> Result<(), EnqueueError> {
+        self.underlying.wait_until_available(manager)
+    }
+}
+
+impl<T: DAMType> Sender<T> {
+    /// Converts the sender into one which can be cloned and shared between multiple owners.
+    pub fn shared(self) -> SharedSender<T> {
+        SharedSender {
+            underlying: Arc::new(Mutex::new(self)),
+        }
+    }
+}
+
+/// A sender which delays every element by a fixed number of ticks, modelling wire delays or pipeline stages.
+/// Capacity and back-pressure are those of the underlying sender.
+pub struct LatencySender<T: Clone> {
+    underlying: Sender<T>,
+    latency: u64,
+}
+
+impl<T: DAMType> LatencySender<T> {
+    /// The added delay, in ticks.
+    pub fn latency(&self) -> u64 {
+        self.latency
+    }
+
+    /// Unwraps the underlying sender.
+    pub fn into_inner(self) -> Sender<T> {
+        self.underlying
+    }
+}
+
+impl<T: DAMType> SendAdapter<T> for LatencySender<T> {
+    fn attach_sender(&self, ctx: &dyn Context) {
+        self.underlying.attach_sender(ctx)
+    }
+
+    fn enqueue(
+        &self,
+        manager: &TimeManager,
+        mut data: ChannelElement<T>,
+    ) -> Result<(), EnqueueError> {
+        data.update_time(data.time + self.latency);
+        self.underlying.enqueue(manager, data)
+    }
+
+    fn wait_until_available(&self, manager: &TimeManager) -> Result<(), EnqueueError> {
+        self.underlying.wait_until_available(manager)
+    }
+}
+
+impl<T: DAMType> Sender<T> {
+    /// Wraps the sender so that every enqueued element is delayed by `ticks`.
+    pub fn with_latency(self, ticks: u64) -> LatencySender<T> {
+        LatencySender {
+            underlying: self,
+            latency: ticks,
+        }
+    }
+}
+
+/// A sender which delays every element by a random number of ticks in `[0, max_jitter)`, drawn uniformly.
+/// Elements are never reordered: an element whose delay would place it before the previous one is sent alongside it instead.
+/// The random number generator is owned by the sender, so seeding it (e.g. with [fastrand::Rng::with_seed]) makes the delays reproducible.
+pub struct JitteredSender<T: Clone> {
+    underlying: Sender<T>,
+    max_jitter: u64,
+    rng: Mutex<fastrand::Rng>,
+    last_sent: Mutex<Time>,
+}
+
+impl<T: DAMType> JitteredSender<T> {
+    /// The exclusive upper bound on the added delay, in ticks.
+    pub fn max_jitter(&self) -> u64 {
+        self.max_jitter
+    }
+
+    /// Unwraps the underlying sender.
+    pub fn into_inner(self) -> Sender<T> {
+        self.underlying
+    }
+
+    fn jitter(&self) -> u64 {
+        if self.max_jitter == 0 {
+            return 0;
+        }
+        self.rng.lock().unwrap().u64(0..self.max_jitter)
+    }
+}
+
+impl<T: DAMType> SendAdapter<T> for JitteredSender<T> {
+    fn attach_sender(&self, ctx: &dyn Context) {
+        self.underlying.attach_sender(ctx)
+    }
+
+    fn enqueue(
+        &self,
+        manager: &TimeManager,
+        mut data: ChannelElement<T>,
+    ) -> Result<(), EnqueueError> {
+        let mut last_sent = self.last_sent.lock().unwrap();
+        *last_sent = (*last_sent).max(data.time + self.jitter());
+        data.update_time(*last_sent);
+        drop(last_sent);
+        self.underlying.enqueue(manager, data)
+    }
+
+    fn wait_until_available(&self, manager: &TimeManager) -> Result<(), EnqueueError> {
+        self.underlying.wait_until_available(manager)
+    }
+}
+
+impl<T: DAMType> Sender<T> {
+    /// Wraps the sender so that every enqueued element is delayed by a random amount in `[0, max_jitter_ticks)`, without reordering elements.
+    pub fn with_jitter(self, max_jitter_ticks: u64, rng: fastrand::Rng) -> JitteredSender<T> {
+        JitteredSender {
+            underlying: self,
+            max_jitter: max_jitter_ticks,
+            rng: Mutex::new(rng),
+            last_sent: Mutex::new(Time::new(0)),
+        }
+    }
+}
+
+/// A receiver which reports every element `latency` ticks earlier than it was sent, undoing a [LatencySender].
+/// Timestamps saturate at zero.
+pub struct LatencyReceiver<T: Clone> {
+    underlying: Receiver<T>,
+    latency: u64,
+}
+
+impl<T: DAMType> LatencyReceiver<T> {
+    /// The removed delay, in ticks.
+    pub fn latency(&self) -> u64 {
+        self.latency
+    }
+
+    /// Unwraps the underlying receiver.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.underlying
+    }
+
+    fn shift(&self, element: ChannelElement<T>) -> ChannelElement<T> {
+        ChannelElement::new(
+            Time::new(element.time.time().saturating_sub(self.latency)),
+            element.data,
+        )
+    }
+}
+
+impl<T: DAMType> RecvAdapter<T> for LatencyReceiver<T> {
+    fn attach_receiver(&self, ctx: &dyn Context) {
+        self.underlying.attach_receiver(ctx)
+    }
+
+    fn peek(&self) -> PeekResult<T> {
+        match self.underlying.peek() {
+            PeekResult::Something(element) => PeekResult::Something(self.shift(element)),
+            other => other,
+        }
+    }
+
+    fn peek_next(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        self.underlying
+            .peek_next(manager)
+            .map(|element| self.shift(element))
+    }
+
+    fn dequeue(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        self.underlying
+            .dequeue(manager)
+            .map(|element| self.shift(element))
+    }
+}
+
+impl<T: DAMType> Receiver<T> {
+    /// Wraps the receiver so that every element is reported `ticks` earlier.
+    pub fn with_latency(self, ticks: u64) -> LatencyReceiver<T> {
+        LatencyReceiver {
+            underlying: self,
+            latency: ticks,
+        }
+    }
+}
+
+/// A sender which refuses to run more than `max_time_ahead` ticks ahead of its receiver.
+/// This provides back-pressure in the time domain, on top of the occupancy-based back-pressure of the channel.
+pub struct FlowControlledSender<T: Clone> {
+    underlying: Sender<T>,
+    max_time_ahead: u64,
+}
+
+impl<T: DAMType> FlowControlledSender<T> {
+    /// The maximum number of ticks the sender may be ahead of the receiver.
+    pub fn max_time_ahead(&self) -> u64 {
+        self.max_time_ahead
+    }
+
+    /// Unwraps the underlying sender.
+    pub fn into_inner(self) -> Sender<T> {
+        self.underlying
+    }
+
+    /// Checks whether the sender is too far ahead of the receiver.
+    /// If it is, returns [SendOptions::CheckBackAt] with the time the receiver needs to reach before sending is allowed.
+    pub fn check_flow(&self, manager: &TimeManager) -> Result<(), SendOptions> {
+        let now = manager.tick();
+        match self.underlying.underlying.spec().receiver_tlb() {
+            Some(receiver_time)
+                if !receiver_time.is_infinite()
+                    && now.time() > receiver_time.time() + self.max_time_ahead =>
+            {
+                Err(SendOptions::CheckBackAt(now - self.max_time_ahead))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Enqueues without waiting on the receiver to catch up.
+    /// If the sender is too far ahead, the element is not sent and [SendOptions::CheckBackAt] is returned.
+    /// If the channel has been closed, [SendOptions::Never] is returned.
+    pub fn try_enqueue(
+        &self,
+        manager: &TimeManager,
+        data: ChannelElement<T>,
+    ) -> Result<(), SendOptions> {
+        self.check_flow(manager)?;
+        self.underlying
+            .enqueue(manager, data)
+            .map_err(|_| SendOptions::Never)
+    }
+
+    fn wait_for_receiver(&self, manager: &TimeManager) {
+        if let Err(SendOptions::CheckBackAt(time)) = self.check_flow(manager) {
+            self.underlying.underlying.spec().wait_until_receiver(time);
+        }
+    }
+}
+
+impl<T: DAMType> SendAdapter<T> for FlowControlledSender<T> {
+    fn attach_sender(&self, ctx: &dyn Context) {
+        self.underlying.attach_sender(ctx)
+    }
+
+    /// Waits for the receiver to catch up if necessary, and then enqueues.
+    fn enqueue(&self, manager: &TimeManager, data: ChannelElement<T>) -> Result<(), EnqueueError> {
+        self.wait_for_receiver(manager);
+        self.underlying.enqueue(manager, data)
+    }
+
+    fn wait_until_available(&self, manager: &TimeManager) -> Result<(), EnqueueError> {
+        self.wait_for_receiver(manager);
+        self.underlying.wait_until_available(manager)
+    }
+}
+
+impl<T: DAMType> Sender<T> {
+    /// Wraps the sender so that it never runs more than `max_time_ahead` ticks ahead of its receiver.
+    pub fn with_flow_control(self, max_time_ahead: u64) -> FlowControlledSender<T> {
+        FlowControlledSender {
+            underlying: self,
+            max_time_ahead,
+        }
+    }
+}
+
+/// A receiver which transforms each element, skipping over the elements for which the transformation returns `None`.
+/// Skipped elements are still dequeued (and acknowledged), and the remaining elements keep their original timestamps.
+pub struct FilterMappedReceiver<T: Clone, U, F>
+where
+    F: Fn(T) -> Option<U>,
+{
+    underlying: Receiver<T>,
+    func: F,
+}
+
+impl<T: DAMType, U, F> FilterMappedReceiver<T, U, F>
+where
+    F: Fn(T) -> Option<U>,
+{
+    /// Unwraps the underlying receiver.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.underlying
+    }
+
+    fn apply(&self, element: ChannelElement<T>) -> Option<ChannelElement<U>> {
+        let time = element.time;
+        (self.func)(element.data).map(|data| ChannelElement::new(time, data))
+    }
+}
+
+impl<T: DAMType, U, F> RecvAdapter<U> for FilterMappedReceiver<T, U, F>
+where
+    F: Fn(T) -> Option<U>,
+{
+    fn attach_receiver(&self, ctx: &dyn Context) {
+        self.underlying.attach_receiver(ctx)
+    }
+
+    /// Skipped elements which the receiver has already reached are consumed while peeking, as that doesn't require advancing time.
+    /// If the head of the channel would be skipped but lies in the future, this instead reports that nothing arrives before it.
+    fn peek(&self) -> PeekResult<U> {
+        loop {
+            match self.underlying.peek() {
+                PeekResult::Something(element) => {
+                    let time = element.time;
+                    if let Some(element) = self.apply(element) {
+                        return PeekResult::Something(element);
+                    }
+                    let reached = self
+                        .underlying
+                        .underlying
+                        .spec()
+                        .receiver_tlb()
+                        .is_some_and(|tick| tick >= time);
+                    if !reached {
+                        return PeekResult::Nothing(Time::new(time.time().saturating_sub(1)));
+                    }
+                    self.underlying.discard_head();
+                }
+                PeekResult::Nothing(time) => return PeekResult::Nothing(time),
+                PeekResult::Closed => return PeekResult::Closed,
+            }
+        }
+    }
+
+    fn peek_next(&self, manager: &TimeManager) -> Result<ChannelElement<U>, DequeueError> {
+        loop {
+            match self.apply(self.underlying.peek_next(manager)?) {
+                Some(element) => return Ok(element),
+                None => self.underlying.advance_head(manager)?,
+            }
+        }
+    }
+
+    fn dequeue(&self, manager: &TimeManager) -> Result<ChannelElement<U>, DequeueError> {
+        loop {
+            if let Some(element) = self.apply(self.underlying.dequeue(manager)?) {
+                return Ok(element);
+            }
+        }
+    }
+}
+
+impl<T: DAMType> Receiver<T> {
+    /// Wraps the receiver so that each element is transformed by `func`, skipping those for which it returns `None`.
+    pub fn filter_map<U, F: Fn(T) -> Option<U>>(self, func: F) -> FilterMappedReceiver<T, U, F> {
+        FilterMappedReceiver {
+            underlying: self,
+            func,
+        }
+    }
+}
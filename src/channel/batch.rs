@@ -0,0 +1,116 @@
+use crate::{datastructures::Time, types::DAMType, view::TimeManager};
+
+use super::{ChannelElement, DequeueError, EnqueueError, Receiver, Sender};
+
+/// A group of elements which travel through a channel as a single unit, such as the lanes of a vector operation.
+/// The channel only sees the `batch_time`, while each element keeps its own timestamp for provenance.
+/// Channels carrying batches are built like any other, e.g. `builder.bounded::<BatchElement<T>>(capacity)`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchElement<T> {
+    /// The grouped elements
+    pub elements: Vec<ChannelElement<T>>,
+    /// The time at which the batch as a whole is available
+    pub batch_time: Time,
+}
+
+impl<T> BatchElement<T> {
+    /// Constructs a batch which is available at an explicit time.
+    pub fn new(elements: Vec<ChannelElement<T>>, batch_time: Time) -> Self {
+        Self {
+            elements,
+            batch_time,
+        }
+    }
+
+    /// Constructs a batch which is available once its latest element is.
+    pub fn from_elements(elements: Vec<ChannelElement<T>>) -> Self {
+        let batch_time = elements
+            .iter()
+            .map(|element| element.time)
+            .max()
+            .unwrap_or_default();
+        Self::new(elements, batch_time)
+    }
+}
+
+impl<T: DAMType> DAMType for BatchElement<T> {
+    fn dam_size(&self) -> usize {
+        self.elements
+            .iter()
+            .map(|element| element.data.dam_size())
+            .sum()
+    }
+}
+
+impl<T: DAMType> Sender<BatchElement<T>> {
+    /// Enqueues an entire batch as a single element, timestamped with its `batch_time`.
+    pub fn enqueue_batch(
+        &self,
+        manager: &TimeManager,
+        batch: BatchElement<T>,
+    ) -> Result<(), EnqueueError> {
+        self.enqueue(manager, ChannelElement::new(batch.batch_time, batch))
+    }
+}
+
+impl<T: DAMType> Receiver<BatchElement<T>> {
+    /// Dequeues an entire batch. The `batch_time` is updated to when the batch actually arrived, which may be later than requested.
+    pub fn dequeue_batch(&self, manager: &TimeManager) -> Result<BatchElement<T>, DequeueError> {
+        let ChannelElement { time, mut data } = self.dequeue(manager)?;
+        data.batch_time = time;
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BatchElement;
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_batch_delivery() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for batch in 0..3u64 {
+                let elements = (0..4)
+                    .map(|lane| ChannelElement::new(time.tick() + lane, batch * 4 + lane))
+                    .collect();
+                snd.enqueue_batch(time, BatchElement::new(elements, time.tick() + 4))
+                    .unwrap();
+                time.incr_cycles(4);
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for batch in 0..3u64 {
+                let received = rcv.dequeue_batch(time).unwrap();
+                assert_eq!(received.batch_time, Time::new(batch * 4 + 4));
+                assert_eq!(time.tick(), received.batch_time);
+                // All four lanes arrive together, with their original timestamps.
+                let lanes: Vec<_> = received.elements.iter().map(|elem| elem.data).collect();
+                assert_eq!(lanes, (batch * 4..batch * 4 + 4).collect::<Vec<_>>());
+                assert_eq!(received.elements[3].time, Time::new(batch * 4 + 3));
+            }
+            assert!(rcv.dequeue_batch(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
@@ -1,9 +1,38 @@
-use std::sync::atomic::AtomicUsize;
+use std::sync::{atomic::AtomicUsize, Arc, OnceLock, RwLock};
 
+use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
 static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
+/// Maps between channels and their debug labels, so that channels can be found by name from anywhere.
+#[derive(Default)]
+struct NameRegistry {
+    // Every live channel holding each name, in the order that they were labelled.
+    by_name: FxHashMap<Arc<str>, Vec<ChannelID>>,
+    by_id: FxHashMap<ChannelID, Arc<str>>,
+}
+
+impl NameRegistry {
+    fn release(&mut self, id: ChannelID) {
+        let Some(old) = self.by_id.remove(&id) else {
+            return;
+        };
+        if let Some(holders) = self.by_name.get_mut(&old) {
+            holders.retain(|holder| *holder != id);
+            if holders.is_empty() {
+                self.by_name.remove(&old);
+            }
+        }
+    }
+}
+
+static NAMES: OnceLock<RwLock<NameRegistry>> = OnceLock::new();
+
+fn names() -> &'static RwLock<NameRegistry> {
+    NAMES.get_or_init(Default::default)
+}
+
 /// A unique identifier for a channel. Not guaranteed stable across program runs.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub struct ChannelID {
@@ -21,6 +50,46 @@ impl ChannelID {
             id: Self::next_id(),
         }
     }
+
+    /// Tags the channel with a namespace, for telling apart channels belonging to different subsystems.
+    pub fn in_namespace(self, namespace: &str) -> NamespacedChannelID {
+        NamespacedChannelID {
+            namespace: namespace.into(),
+            id: self,
+        }
+    }
+
+    /// Looks up a channel by its debug label, see [super::Sender::set_debug_label].
+    /// If several live channels share a label, this returns the one which was labelled most recently.
+    /// Names are released once the channel is dropped, so labels can be reused by later programs.
+    pub fn from_name(name: &str) -> Option<ChannelID> {
+        names()
+            .read()
+            .unwrap()
+            .by_name
+            .get(name)
+            .and_then(|holders| holders.last().copied())
+    }
+
+    /// Gets the debug label of the channel, if one was set.
+    pub fn name(&self) -> Option<Arc<str>> {
+        names().read().unwrap().by_id.get(self).cloned()
+    }
+
+    pub(crate) fn register_name(self, name: Arc<str>) {
+        let mut registry = names().write().unwrap();
+        registry.release(self);
+        registry.by_id.insert(self, name.clone());
+        registry.by_name.entry(name).or_default().push(self);
+    }
+
+    pub(crate) fn unregister_name(self) {
+        names().write().unwrap().release(self);
+    }
+
+    pub(crate) fn as_usize(&self) -> usize {
+        self.id
+    }
 }
 
 impl Default for ChannelID {
@@ -34,3 +103,18 @@ impl std::fmt::Display for ChannelID {
         write!(f, "Channel({})", self.id)
     }
 }
+
+/// A [ChannelID] tagged with the namespace of the subsystem that created it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NamespacedChannelID {
+    /// The namespace of the channel
+    pub namespace: Arc<str>,
+    /// The underlying channel
+    pub id: ChannelID,
+}
+
+impl std::fmt::Display for NamespacedChannelID {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::chan-{}", self.namespace, self.id.id)
+    }
+}
@@ -1,12 +1,20 @@
-use crate::shim::Mutex;
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::shim::{Mutex, RwLock};
 
 use crate::{
     context::Context,
     datastructures::{Identifier, Time},
-    view::{ContextView, TimeView},
+    view::{ContextView, TimeView, TimeoutPolicy},
 };
 
-use super::ChannelID;
+use super::{
+    observer::OccupancyMonitor, ChannelEvent, ChannelID, ChannelObserver, MonitorID,
+    NamespacedChannelID, StallHistogram,
+};
 
 type ViewType = Option<TimeView>;
 
@@ -18,9 +26,29 @@ pub(crate) struct ChannelSpec {
     sender_id: Mutex<Option<Identifier>>,
     receiver_id: Mutex<Option<Identifier>>,
     channel_id: ChannelID,
+    namespace: Option<Arc<str>>,
     capacity: Option<usize>,
     send_latency: u64,
     response_latency: u64,
+
+    stall_histogram: Mutex<StallHistogram>,
+    debug_label: Mutex<Option<Arc<str>>>,
+
+    // The tick after which the channel closes, or u64::MAX if it stays open. Read on every send, so it avoids taking a lock.
+    close_tick: AtomicU64,
+
+    timeout_policy: Mutex<TimeoutPolicy>,
+
+    // Checked before taking the lock, so that channels without observers don't pay for them.
+    has_observers: AtomicBool,
+    observers: RwLock<Vec<ChannelObserver>>,
+
+    // Also checked before taking the lock, see has_observers.
+    has_monitors: AtomicBool,
+    monitors: Mutex<Vec<OccupancyMonitor>>,
+    next_monitor: AtomicUsize,
+
+    message_count: AtomicU64,
 }
 
 /// An inline version of the specification. This avoids needing an extra Arc/indirection to get back to the original object.
@@ -29,6 +57,10 @@ pub(crate) struct InlineSpec {
     pub send_latency: u64,
     pub response_latency: u64,
 
+    // Labels may be set after initialization, so panic messages read them from the shared spec.
+    shared: Arc<ChannelSpec>,
+    timeout_policy: TimeoutPolicy,
+
     sender_view: ViewType,
     receiver_view: ViewType,
 }
@@ -49,9 +81,125 @@ impl ChannelSpec {
             sender_id: Mutex::new(None),
             receiver_id: Mutex::new(None),
             channel_id: ChannelID::new(),
+            namespace: None,
             capacity,
             send_latency: lat,
             response_latency: resp_lat,
+            stall_histogram: Default::default(),
+            debug_label: Mutex::new(None),
+            close_tick: AtomicU64::new(u64::MAX),
+            timeout_policy: Mutex::new(TimeoutPolicy::default()),
+            has_observers: AtomicBool::new(false),
+            observers: RwLock::new(vec![]),
+            has_monitors: AtomicBool::new(false),
+            monitors: Mutex::new(vec![]),
+            next_monitor: AtomicUsize::new(0),
+            message_count: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_namespace(mut self, namespace: Arc<str>) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
+    pub fn namespaced_id(&self) -> Option<NamespacedChannelID> {
+        self.namespace
+            .as_ref()
+            .map(|namespace| NamespacedChannelID {
+                namespace: namespace.clone(),
+                id: self.channel_id,
+            })
+    }
+
+    pub fn set_debug_label(&self, label: &str) {
+        let label: Arc<str> = label.into();
+        self.channel_id.register_name(label.clone());
+        *self.debug_label.lock().unwrap() = Some(label);
+    }
+
+    pub fn debug_label(&self) -> Option<Arc<str>> {
+        self.debug_label.lock().unwrap().clone()
+    }
+
+    pub fn set_close_tick(&self, tick: Time) {
+        // An infinite tick never closes the channel, which is the same as not having one.
+        let tick = if tick.is_infinite() {
+            u64::MAX
+        } else {
+            tick.time()
+        };
+        self.close_tick.store(tick, Ordering::Relaxed);
+    }
+
+    pub fn close_tick(&self) -> Option<Time> {
+        match self.close_tick.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            tick => Some(Time::new(tick)),
+        }
+    }
+
+    pub fn record_message(&self) {
+        self.message_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn message_count(&self) -> u64 {
+        self.message_count.load(Ordering::Relaxed)
+    }
+
+    pub fn add_observer(&self, observer: ChannelObserver) {
+        self.observers.write().unwrap().push(observer);
+        self.has_observers.store(true, Ordering::Release);
+    }
+
+    pub fn has_observers(&self) -> bool {
+        self.has_observers.load(Ordering::Acquire)
+    }
+
+    pub fn notify(&self, event: ChannelEvent) {
+        if self.has_observers() {
+            self.observers
+                .read()
+                .unwrap()
+                .iter()
+                .for_each(|observer| observer(event));
+        }
+    }
+
+    pub fn add_monitor(&self, threshold: f64, callback: Box<dyn Fn(f64) + Send>) -> MonitorID {
+        let id = MonitorID(self.next_monitor.fetch_add(1, Ordering::Relaxed));
+        let mut monitors = self.monitors.lock().unwrap();
+        monitors.push(OccupancyMonitor::new(id, threshold, callback));
+        self.has_monitors.store(true, Ordering::Release);
+        id
+    }
+
+    pub fn remove_monitor(&self, id: MonitorID) -> bool {
+        let mut monitors = self.monitors.lock().unwrap();
+        let before = monitors.len();
+        monitors.retain(|monitor| monitor.id != id);
+        self.has_monitors
+            .store(!monitors.is_empty(), Ordering::Release);
+        monitors.len() != before
+    }
+
+    pub fn has_monitors(&self) -> bool {
+        self.has_monitors.load(Ordering::Acquire)
+    }
+
+    pub fn update_monitors(&self, occupancy: f64) {
+        self.monitors
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .for_each(|monitor| monitor.update(occupancy));
+    }
+
+    /// A human-readable name for the channel, including its debug label if one was set.
+    pub fn describe(&self) -> String {
+        match self.debug_label() {
+            Some(label) => format!("Channel {} ({label})", self.channel_id.as_usize()),
+            None => format!("Channel {}", self.channel_id.as_usize()),
         }
     }
 
@@ -84,6 +232,46 @@ impl ChannelSpec {
         *self.receiver_id.lock().unwrap() = Some(receiver.id());
     }
 
+    pub fn record_stall(&self, duration: u64) {
+        self.stall_histogram.lock().unwrap().record(duration);
+    }
+
+    pub fn stall_histogram(&self) -> StallHistogram {
+        self.stall_histogram.lock().unwrap().clone()
+    }
+
+    /// The tick lower bound of the receiver, if one has been attached.
+    pub fn receiver_tlb(&self) -> Option<Time> {
+        self.receiver_view
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|view| view.tick_lower_bound())
+    }
+
+    /// Blocks until the receiver reaches the given time, if one has been attached.
+    pub fn wait_until_receiver(&self, time: Time) -> Option<Time> {
+        // Don't hold onto the lock while waiting.
+        let view = self.receiver_view.lock().unwrap().clone();
+        view.map(|view| view.wait_until(time))
+    }
+
+    /// Like waiting on the sender, but gives up after `timeout` of wall-clock time, returning None.
+    /// Without an attached sender, there is nothing to wait on.
+    pub fn try_wait_until_sender(&self, time: Time, timeout: std::time::Duration) -> Option<Time> {
+        // Don't hold onto the lock while waiting.
+        let view = self.sender_view.lock().unwrap().clone();
+        match view {
+            Some(view) => view.try_wait_until(time, timeout),
+            None => Some(time),
+        }
+    }
+
+    /// Sets how long the channel's endpoints wait on each other. This takes effect when the channel is initialized.
+    pub fn set_timeout_policy(&self, policy: TimeoutPolicy) {
+        *self.timeout_policy.lock().unwrap() = policy;
+    }
+
     pub fn capacity(&self) -> Option<usize> {
         self.capacity
     }
@@ -92,20 +280,36 @@ impl ChannelSpec {
         self.channel_id
     }
 
-    pub(crate) fn make_inline(&self) -> InlineSpec {
+    pub(crate) fn make_inline(self: &Arc<Self>) -> InlineSpec {
         InlineSpec {
             capacity: self.capacity,
             send_latency: self.send_latency,
             response_latency: self.response_latency,
+            shared: self.clone(),
+            timeout_policy: *self.timeout_policy.lock().unwrap(),
             sender_view: self.sender_view.lock().unwrap().clone(),
             receiver_view: self.receiver_view.lock().unwrap().clone(),
         }
     }
 }
 
+impl Drop for ChannelSpec {
+    fn drop(&mut self) {
+        // Names are looked up globally, so they must not outlive the channel.
+        if self.debug_label.lock().unwrap().is_some() {
+            self.channel_id.unregister_name();
+        }
+    }
+}
+
 impl InlineSpec {
+    /// Identifies the channel in panic messages, see [ChannelSpec::describe]
+    pub fn describe(&self) -> String {
+        self.shared.describe()
+    }
+
     pub fn wait_until_sender(&self, time: Time) -> Time {
-        self.sender_view.as_ref().unwrap().wait_until(time)
+        self.wait_on(self.sender_view.as_ref().unwrap(), time, "sender")
     }
 
     pub fn sender_tlb(&self) -> Time {
@@ -113,7 +317,21 @@ impl InlineSpec {
     }
 
     pub fn wait_until_receiver(&self, time: Time) -> Time {
-        self.receiver_view.as_ref().unwrap().wait_until(time)
+        self.wait_on(self.receiver_view.as_ref().unwrap(), time, "receiver")
+    }
+
+    fn wait_on(&self, view: &TimeView, time: Time, end: &str) -> Time {
+        match self.timeout_policy {
+            TimeoutPolicy::Unbounded => view.wait_until(time),
+            TimeoutPolicy::PanicAfter(timeout) => {
+                view.try_wait_until(time, timeout).unwrap_or_else(|| {
+                    panic!(
+                        "{}: Timed out after {timeout:?} waiting for the {end} to reach {time:?}",
+                        self.describe()
+                    )
+                })
+            }
+        }
     }
 
     pub fn receiver_tlb(&self) -> Time {
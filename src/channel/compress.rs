@@ -0,0 +1,118 @@
+use std::marker::PhantomData;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{datastructures::Time, types::DAMType};
+
+use super::ChannelElement;
+
+/// A channel payload holding a bincode-serialized, LZ4-compressed `T`.
+/// This trades decompression time for memory when channels hold onto many large elements.
+pub struct Compressed<T> {
+    bytes: Vec<u8>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize> Compressed<T> {
+    /// Serializes and compresses a value.
+    pub fn new(data: &T) -> Self {
+        let serialized = bincode::serialize(data).expect("Failed to serialize compressed data");
+        Self {
+            bytes: lz4_flex::compress_prepend_size(&serialized),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Compressed<T> {
+    /// Decompresses and deserializes the value.
+    pub fn decompress(&self) -> T {
+        let serialized = lz4_flex::decompress_size_prepended(&self.bytes)
+            .expect("Failed to decompress compressed data");
+        bincode::deserialize(&serialized).expect("Failed to deserialize compressed data")
+    }
+}
+
+impl<T> Compressed<T> {
+    /// The size of the compressed representation, in bytes.
+    pub fn compressed_len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+// Manual impls, so that T itself does not need to satisfy these bounds.
+impl<T> Clone for Compressed<T> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for Compressed<T> {
+    fn default() -> Self {
+        Self {
+            bytes: vec![],
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for Compressed<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Compressed")
+            .field("compressed_len", &self.bytes.len())
+            .finish()
+    }
+}
+
+impl<T> DAMType for Compressed<T> {
+    fn dam_size(&self) -> usize {
+        self.bytes.len() * 8
+    }
+}
+
+/// A [ChannelElement] whose payload is compressed.
+pub type CompressedElement<T> = ChannelElement<Compressed<T>>;
+
+impl<T: Serialize> ChannelElement<T> {
+    /// Constructs a compressed element directly from a timestamp and value.
+    pub fn compressed(time: Time, data: T) -> CompressedElement<T> {
+        ChannelElement::new(time, data).compress()
+    }
+
+    /// Compresses the payload of the element, keeping its timestamp.
+    pub fn compress(self) -> CompressedElement<T> {
+        ChannelElement {
+            time: self.time,
+            data: Compressed::new(&self.data),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> CompressedElement<T> {
+    /// Decompresses the payload of the element, keeping its timestamp.
+    pub fn decompress(self) -> ChannelElement<T> {
+        ChannelElement {
+            time: self.time,
+            data: self.data.decompress(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{channel::ChannelElement, datastructures::Time};
+
+    #[test]
+    fn test_compress_round_trip() {
+        let data: Vec<u64> = vec![42; 4096];
+        let element = ChannelElement::compressed(Time::new(7), data.clone());
+        assert!(element.data.compressed_len() < data.len() * std::mem::size_of::<u64>());
+
+        let restored = element.decompress();
+        assert_eq!(restored.time, Time::new(7));
+        assert_eq!(restored.data, data);
+    }
+}
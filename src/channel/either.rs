@@ -0,0 +1,199 @@
+use std::collections::VecDeque;
+
+use crate::{context::Context, types::DAMType, view::TimeManager};
+
+use super::{ChannelElement, DequeueError, EnqueueError, Receiver, Sender};
+
+/// One of two alternatives, such as the routing decision of a [crate::utility_contexts::SplitContext].
+/// This is also the payload of channels which multiplex two streams, see [MuxSender].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Either<U, V> {
+    /// The left alternative
+    Left(U),
+    /// The right alternative
+    Right(V),
+}
+
+impl<U: Default, V> Default for Either<U, V> {
+    fn default() -> Self {
+        Either::Left(U::default())
+    }
+}
+
+impl<U: DAMType, V: DAMType> DAMType for Either<U, V> {
+    fn dam_size(&self) -> usize {
+        match self {
+            Either::Left(left) => left.dam_size(),
+            Either::Right(right) => right.dam_size(),
+        }
+    }
+}
+
+/// A sender which multiplexes two streams of different types onto a single channel of [Either].
+/// The two streams share the channel's capacity, so a stalled stream also stalls the other.
+pub struct MuxSender<T: Clone, U: Clone> {
+    sender: Sender<Either<T, U>>,
+}
+
+impl<T: DAMType, U: DAMType> MuxSender<T, U> {
+    /// Wraps a sender of multiplexed elements.
+    pub fn new(sender: Sender<Either<T, U>>) -> Self {
+        Self { sender }
+    }
+
+    /// See: [Sender::attach_sender]
+    pub fn attach_sender(&self, sender: &dyn Context) {
+        self.sender.attach_sender(sender)
+    }
+
+    /// Enqueues an element of the left stream.
+    pub fn send_left(
+        &self,
+        manager: &TimeManager,
+        element: ChannelElement<T>,
+    ) -> Result<(), EnqueueError> {
+        let ChannelElement { time, data } = element;
+        self.sender
+            .enqueue(manager, ChannelElement::new(time, Either::Left(data)))
+    }
+
+    /// Enqueues an element of the right stream.
+    pub fn send_right(
+        &self,
+        manager: &TimeManager,
+        element: ChannelElement<U>,
+    ) -> Result<(), EnqueueError> {
+        let ChannelElement { time, data } = element;
+        self.sender
+            .enqueue(manager, ChannelElement::new(time, Either::Right(data)))
+    }
+
+    /// Unwraps the underlying sender.
+    pub fn into_inner(self) -> Sender<Either<T, U>> {
+        self.sender
+    }
+}
+
+/// A receiver which demultiplexes a channel of [Either] back into its two streams, the counterpart to [MuxSender].
+/// Reading one stream buffers any elements of the other stream which arrive first, so each stream is seen in order.
+pub struct SplitReceiver<T: Clone, U: Clone> {
+    receiver: Receiver<Either<T, U>>,
+    left: VecDeque<ChannelElement<T>>,
+    right: VecDeque<ChannelElement<U>>,
+}
+
+impl<T: DAMType, U: DAMType> SplitReceiver<T, U> {
+    /// Wraps a receiver of multiplexed elements.
+    pub fn new(receiver: Receiver<Either<T, U>>) -> Self {
+        Self {
+            receiver,
+            left: VecDeque::new(),
+            right: VecDeque::new(),
+        }
+    }
+
+    /// See: [Receiver::attach_receiver]
+    pub fn attach_receiver(&self, receiver: &dyn Context) {
+        self.receiver.attach_receiver(receiver)
+    }
+
+    /// Dequeues the next element of the left stream, buffering any right elements before it.
+    /// If the channel is closed before another left element arrives, then it returns a DequeueError instead.
+    pub fn recv_left(&mut self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        if let Some(element) = self.left.pop_front() {
+            return Ok(element);
+        }
+        loop {
+            let ChannelElement { time, data } = self.receiver.dequeue(manager)?;
+            match data {
+                Either::Left(left) => return Ok(ChannelElement::new(time, left)),
+                Either::Right(right) => self.right.push_back(ChannelElement::new(time, right)),
+            }
+        }
+    }
+
+    /// Dequeues the next element of the right stream, buffering any left elements before it.
+    /// If the channel is closed before another right element arrives, then it returns a DequeueError instead.
+    pub fn recv_right(&mut self, manager: &TimeManager) -> Result<ChannelElement<U>, DequeueError> {
+        if let Some(element) = self.right.pop_front() {
+            return Ok(element);
+        }
+        loop {
+            let ChannelElement { time, data } = self.receiver.dequeue(manager)?;
+            match data {
+                Either::Left(left) => self.left.push_back(ChannelElement::new(time, left)),
+                Either::Right(right) => return Ok(ChannelElement::new(time, right)),
+            }
+        }
+    }
+
+    /// The number of elements buffered for the left and right streams respectively.
+    pub fn buffered(&self) -> (usize, usize) {
+        (self.left.len(), self.right.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MuxSender, SplitReceiver};
+    use crate::{
+        channel::ChannelElement,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_interleaved_demux() {
+        const NUM_ELEMENTS: u64 = 64;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let sender = MuxSender::new(snd);
+        let mut producer = FunctionContext::default();
+        sender.attach_sender(&producer);
+        producer.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                // Two left elements for every right element.
+                if iter % 3 == 2 {
+                    sender
+                        .send_right(time, ChannelElement::new(time.tick(), iter as f64))
+                        .unwrap();
+                } else {
+                    sender
+                        .send_left(time, ChannelElement::new(time.tick(), iter))
+                        .unwrap();
+                }
+                time.incr_cycles(1);
+            }
+        });
+        ctx.add_child(producer);
+
+        let mut receiver = SplitReceiver::new(rcv);
+        let mut consumer = FunctionContext::default();
+        receiver.attach_receiver(&consumer);
+        consumer.set_run(move |time| {
+            // Reading the right stream to completion buffers the entire left stream.
+            for iter in (0..NUM_ELEMENTS).filter(|iter| iter % 3 == 2) {
+                let element = receiver.recv_right(time).unwrap();
+                assert_eq!(element.data, iter as f64);
+                assert_eq!(element.time.time(), iter);
+            }
+            assert!(receiver.recv_right(time).is_err());
+            assert_eq!(receiver.buffered().1, 0);
+            for iter in (0..NUM_ELEMENTS).filter(|iter| iter % 3 != 2) {
+                let element = receiver.recv_left(time).unwrap();
+                assert_eq!(element.data, iter);
+                assert_eq!(element.time.time(), iter);
+            }
+            assert!(receiver.recv_left(time).is_err());
+            assert_eq!(receiver.buffered(), (0, 0));
+        });
+        ctx.add_child(consumer);
+
+        let executed = ctx
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
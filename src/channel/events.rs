@@ -1,7 +1,10 @@
 use dam_macros::event_type_internal;
 use serde::{Deserialize, Serialize};
 
-use crate::datastructures::Identifier;
+use crate::{
+    datastructures::{Identifier, Time},
+    logging::{LogEntry, LogEvent},
+};
 
 use super::ChannelID;
 
@@ -13,6 +16,7 @@ pub enum SendEvent {
     EnqueueFinish(ChannelID),
     AttachSender(ChannelID, Identifier),
     Cleanup(ChannelID),
+    InjectToken(ChannelID, Time),
 }
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
@@ -26,3 +30,46 @@ pub enum ReceiverEvent {
     AttachReceiver(ChannelID, Identifier),
     Cleanup(ChannelID),
 }
+
+impl SendEvent {
+    fn channel(&self) -> ChannelID {
+        match self {
+            SendEvent::TrySend(id)
+            | SendEvent::EnqueueStart(id)
+            | SendEvent::EnqueueFinish(id)
+            | SendEvent::AttachSender(id, _)
+            | SendEvent::Cleanup(id)
+            | SendEvent::InjectToken(id, _) => *id,
+        }
+    }
+}
+
+impl ReceiverEvent {
+    fn channel(&self) -> ChannelID {
+        match self {
+            ReceiverEvent::Peek(id)
+            | ReceiverEvent::PeekNextStart(id)
+            | ReceiverEvent::PeekNextFinish(id)
+            | ReceiverEvent::DequeueStart(id)
+            | ReceiverEvent::DequeueFinish(id)
+            | ReceiverEvent::AttachReceiver(id, _)
+            | ReceiverEvent::Cleanup(id) => *id,
+        }
+    }
+}
+
+/// Extracts the channel that a log entry refers to, if it is a channel event.
+pub(crate) fn channel_of(entry: &LogEntry) -> Option<ChannelID> {
+    let data = entry.event_data.clone();
+    if entry.event_type == SendEvent::NAME {
+        bson::from_bson::<SendEvent>(data)
+            .ok()
+            .map(|event| event.channel())
+    } else if entry.event_type == ReceiverEvent::NAME {
+        bson::from_bson::<ReceiverEvent>(data)
+            .ok()
+            .map(|event| event.channel())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{context::Context, datastructures::Time, types::DAMType, view::TimeManager};
+
+use super::{ChannelElement, DequeueError, EnqueueError, Receiver, Sender};
+
+/// An element which is only useful until its deadline, after which it is considered stale.
+/// Channels carrying these are built like any other, e.g. `builder.bounded::<ExpiringElement<T>>(capacity)`, and are read through an [ExpiringReceiver].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpiringElement<T> {
+    /// The wrapped element
+    pub element: ChannelElement<T>,
+    /// The element is stale once the receiver has reached this time
+    pub deadline: Time,
+}
+
+impl<T: Default> Default for ExpiringElement<T> {
+    fn default() -> Self {
+        Self {
+            element: ChannelElement::new(Time::default(), T::default()),
+            deadline: Time::infinite(),
+        }
+    }
+}
+
+impl<T: DAMType> DAMType for ExpiringElement<T> {
+    fn dam_size(&self) -> usize {
+        self.element.data.dam_size()
+    }
+}
+
+impl<T> ChannelElement<T> {
+    /// Attaches a deadline to the element, after which it should be discarded instead of consumed.
+    pub fn expires_at(self, deadline: Time) -> ExpiringElement<T> {
+        ExpiringElement {
+            element: self,
+            deadline,
+        }
+    }
+}
+
+impl<T: DAMType> Sender<ExpiringElement<T>> {
+    /// Enqueues an expiring element, timestamped with the time of the wrapped element.
+    pub fn enqueue_expiring(
+        &self,
+        manager: &TimeManager,
+        element: ExpiringElement<T>,
+    ) -> Result<(), EnqueueError> {
+        self.enqueue(manager, ChannelElement::new(element.element.time, element))
+    }
+}
+
+/// A receiver which silently discards elements that have passed their deadline by the time they are dequeued.
+pub struct ExpiringReceiver<T: Clone> {
+    receiver: Receiver<ExpiringElement<T>>,
+    misses: AtomicU64,
+}
+
+impl<T: DAMType> ExpiringReceiver<T> {
+    /// Wraps a receiver of expiring elements.
+    pub fn new(receiver: Receiver<ExpiringElement<T>>) -> Self {
+        Self {
+            receiver,
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// See: [Receiver::attach_receiver]
+    pub fn attach_receiver(&self, receiver: &dyn Context) {
+        self.receiver.attach_receiver(receiver)
+    }
+
+    /// Dequeues the next element which has not yet expired, discarding (and acknowledging) any stale elements before it.
+    /// An element is stale if its deadline is at or before the time at which it was dequeued.
+    /// The returned element is timestamped with when it actually arrived.
+    pub fn recv(&self, manager: &TimeManager) -> Result<ExpiringElement<T>, DequeueError> {
+        loop {
+            let ChannelElement { time, mut data } = self.receiver.dequeue(manager)?;
+            if data.deadline <= manager.tick() {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            data.element.update_time(time);
+            return Ok(data);
+        }
+    }
+
+    /// The number of elements which were discarded for having expired.
+    pub fn miss_count(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Unwraps the underlying receiver.
+    pub fn into_inner(self) -> Receiver<ExpiringElement<T>> {
+        self.receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExpiringReceiver;
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_slow_consumer() {
+        const NUM_ELEMENTS: u64 = 64;
+        const LIFETIME: u64 = 8;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 1..=NUM_ELEMENTS {
+                let element = ChannelElement::new(Time::new(iter), iter);
+                snd.enqueue_expiring(time, element.expires_at(Time::new(iter + LIFETIME)))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let receiver = ExpiringReceiver::new(rcv);
+        let mut consumer = FunctionContext::default();
+        receiver.attach_receiver(&consumer);
+        consumer.set_run(move |time| {
+            let mut received = 0;
+            while let Ok(element) = receiver.recv(time) {
+                assert!(element.deadline > time.tick());
+                assert!(element.element.time <= time.tick());
+                received += 1;
+                // Consumes elements at a third of the rate that they are produced.
+                time.incr_cycles(3);
+            }
+            assert!(receiver.miss_count() > 0);
+            assert_eq!(received + receiver.miss_count(), NUM_ELEMENTS);
+        });
+        ctx.add_child(consumer);
+
+        let executed = ctx
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
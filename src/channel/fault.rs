@@ -0,0 +1,251 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+use crate::{context::Context, shim::Mutex, types::DAMType, view::TimeManager};
+
+use super::{ChannelElement, DequeueError, EnqueueError, Receiver, Sender};
+
+/// Data which travels alongside a CRC32 checksum of its serialized form, so that corruption can be detected by the receiver.
+/// Channels carrying these are built like any other, e.g. `builder.bounded::<Faultable<T>>(capacity)`,
+/// and are written through a [FaultInjector] and read through a [FaultDetector].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Faultable<T> {
+    /// The payload
+    pub data: T,
+    /// The checksum which the payload is expected to match
+    pub checksum: u32,
+}
+
+impl<T: Serialize> Faultable<T> {
+    /// Wraps a payload along with its checksum.
+    pub fn new(data: T) -> Self {
+        let checksum = checksum(&data);
+        Self { data, checksum }
+    }
+
+    /// Whether the payload still matches its checksum.
+    pub fn is_intact(&self) -> bool {
+        checksum(&self.data) == self.checksum
+    }
+}
+
+impl<T: DAMType> DAMType for Faultable<T> {
+    fn dam_size(&self) -> usize {
+        self.data.dam_size() + 32
+    }
+}
+
+/// The result of reading from a [FaultDetector].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Recv<T> {
+    /// The element matched its checksum
+    Valid(ChannelElement<T>),
+    /// The element did not match its checksum
+    Corrupt(ChannelElement<T>),
+}
+
+/// The CRC32 (IEEE) of the payload's JSON serialization.
+fn checksum<T: Serialize>(data: &T) -> u32 {
+    crc32(&serde_json::to_vec(data).expect("Failed to serialize faultable data"))
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// A sender which corrupts the checksum of a random fraction of the elements it sends, modeling unreliable hardware such as DRAM with bit errors.
+pub struct FaultInjector<T: Clone> {
+    sender: Sender<Faultable<T>>,
+    fault_rate: f64,
+    rng: Mutex<fastrand::Rng>,
+    injected: AtomicU64,
+}
+
+impl<T: DAMType + Serialize> FaultInjector<T> {
+    /// Wraps a sender, corrupting each element with probability `fault_rate`.
+    /// Seeding `rng` makes the injected faults reproducible.
+    pub fn new(sender: Sender<Faultable<T>>, fault_rate: f64, rng: fastrand::Rng) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&fault_rate),
+            "Fault rate must be between 0 and 1, got {fault_rate}"
+        );
+        Self {
+            sender,
+            fault_rate,
+            rng: Mutex::new(rng),
+            injected: AtomicU64::new(0),
+        }
+    }
+
+    /// See: [Sender::attach_sender]
+    pub fn attach_sender(&self, sender: &dyn Context) {
+        self.sender.attach_sender(sender)
+    }
+
+    /// Checksums and enqueues an element, possibly corrupting its checksum.
+    pub fn send(
+        &self,
+        manager: &TimeManager,
+        element: ChannelElement<T>,
+    ) -> Result<(), EnqueueError> {
+        let ChannelElement { time, data } = element;
+        let mut faultable = Faultable::new(data);
+        let mut rng = self.rng.lock().unwrap();
+        if rng.f64() < self.fault_rate {
+            // Flips at least one bit, so the checksum can never match.
+            faultable.checksum ^= rng.u32(1..);
+            self.injected.fetch_add(1, Ordering::Relaxed);
+        }
+        drop(rng);
+        self.sender
+            .enqueue(manager, ChannelElement::new(time, faultable))
+    }
+
+    /// The number of elements which were corrupted.
+    pub fn injected_count(&self) -> u64 {
+        self.injected.load(Ordering::Relaxed)
+    }
+
+    /// Unwraps the underlying sender.
+    pub fn into_inner(self) -> Sender<Faultable<T>> {
+        self.sender
+    }
+}
+
+/// A receiver which verifies the checksum of each element it dequeues.
+pub struct FaultDetector<T: Clone> {
+    receiver: Receiver<Faultable<T>>,
+    corrupt: AtomicU64,
+}
+
+impl<T: DAMType + Serialize> FaultDetector<T> {
+    /// Wraps a receiver of checksummed elements.
+    pub fn new(receiver: Receiver<Faultable<T>>) -> Self {
+        Self {
+            receiver,
+            corrupt: AtomicU64::new(0),
+        }
+    }
+
+    /// See: [Receiver::attach_receiver]
+    pub fn attach_receiver(&self, receiver: &dyn Context) {
+        self.receiver.attach_receiver(receiver)
+    }
+
+    /// Dequeues the next element, reporting whether it matched its checksum.
+    /// Corrupt elements are still returned, so that the caller can decide how to recover.
+    pub fn recv(&self, manager: &TimeManager) -> Result<Recv<T>, DequeueError> {
+        let ChannelElement { time, data } = self.receiver.dequeue(manager)?;
+        let intact = data.is_intact();
+        let element = ChannelElement::new(time, data.data);
+        if intact {
+            Ok(Recv::Valid(element))
+        } else {
+            self.corrupt.fetch_add(1, Ordering::Relaxed);
+            Ok(Recv::Corrupt(element))
+        }
+    }
+
+    /// The number of elements which failed their checksum.
+    pub fn corrupt_count(&self) -> u64 {
+        self.corrupt.load(Ordering::Relaxed)
+    }
+
+    /// Unwraps the underlying receiver.
+    pub fn into_inner(self) -> Receiver<Faultable<T>> {
+        self.receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    };
+
+    use super::{crc32, FaultDetector, FaultInjector, Faultable, Recv};
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_checksum() {
+        // The standard CRC32 check value.
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        let element = Faultable::new(42u64);
+        assert!(element.is_intact());
+        assert!(!Faultable {
+            checksum: element.checksum ^ 1,
+            ..element
+        }
+        .is_intact());
+    }
+
+    #[test]
+    fn test_detection_rate() {
+        const NUM_ELEMENTS: u64 = 10000;
+        const FAULT_RATE: f64 = 0.1;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(8);
+        let injected = Arc::new(AtomicU64::new(0));
+
+        let injector = FaultInjector::new(snd, FAULT_RATE, fastrand::Rng::with_seed(0));
+        let mut sender = FunctionContext::default();
+        injector.attach_sender(&sender);
+        let injected_count = injected.clone();
+        sender.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                injector
+                    .send(time, ChannelElement::new(Time::new(iter), iter))
+                    .unwrap();
+            }
+            injected_count.store(injector.injected_count(), Ordering::Relaxed);
+        });
+        ctx.add_child(sender);
+
+        let detector = FaultDetector::new(rcv);
+        let mut receiver = FunctionContext::default();
+        detector.attach_receiver(&receiver);
+        let detected = Arc::new(AtomicU64::new(0));
+        let detected_count = detected.clone();
+        receiver.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                match detector.recv(time).unwrap() {
+                    Recv::Valid(element) | Recv::Corrupt(element) => {
+                        assert_eq!(element.data, iter)
+                    }
+                }
+            }
+            detected_count.store(detector.corrupt_count(), Ordering::Relaxed);
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+
+        let detected = detected.load(Ordering::Relaxed);
+        assert_eq!(detected, injected.load(Ordering::Relaxed));
+        let rate = detected as f64 / NUM_ELEMENTS as f64;
+        assert!(
+            (rate - FAULT_RATE).abs() < 0.02,
+            "Detection rate was {rate}"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum ChannelFlavor {
     Acyclic,
     Cyclic,
@@ -0,0 +1,290 @@
+use crate::{context::Context, datastructures::Time, types::DAMType, view::TimeManager};
+
+use super::{
+    utils::{EventTime, Peekable},
+    ChannelElement, DequeueError, Receiver,
+};
+
+/// How a [ReceiverGroup] picks between receivers which have elements ready at the same tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Prefer the receiver with the lowest index.
+    #[default]
+    Lowest,
+
+    /// Prefer the receiver with the highest index.
+    Highest,
+
+    /// Rotate priority, starting after the receiver which was selected last.
+    RoundRobin,
+}
+
+/// A collection of receivers of the same type, for contexts which wish to consume from whichever input is ready first.
+pub struct ReceiverGroup<T: Clone> {
+    receivers: Vec<Receiver<T>>,
+    closed: Vec<bool>,
+    tie_break: TieBreak,
+    next_index: usize,
+}
+
+impl<T: DAMType> ReceiverGroup<T> {
+    /// Constructs a new group over the given receivers.
+    pub fn new(receivers: Vec<Receiver<T>>, tie_break: TieBreak) -> Self {
+        let closed = vec![false; receivers.len()];
+        Self {
+            receivers,
+            closed,
+            tie_break,
+            next_index: 0,
+        }
+    }
+
+    /// Attaches all of the receivers in the group to a context.
+    pub fn attach_receiver(&self, receiver: &dyn Context) {
+        self.receivers
+            .iter()
+            .for_each(|recv| recv.attach_receiver(receiver));
+    }
+
+    /// The underlying receivers, in index order.
+    pub fn receivers(&self) -> &[Receiver<T>] {
+        &self.receivers
+    }
+
+    /// Number of receivers in the group.
+    pub fn len(&self) -> usize {
+        self.receivers.len()
+    }
+
+    /// Whether the group contains no receivers.
+    pub fn is_empty(&self) -> bool {
+        self.receivers.is_empty()
+    }
+
+    /// Returns true once every receiver in the group has been observed as closed.
+    pub fn all_closed(&self) -> bool {
+        self.closed.iter().all(|closed| *closed)
+    }
+
+    /// Advances time until some receiver has an element, and dequeues the earliest one along with the index of its receiver.
+    /// An element is only selected once every other receiver is known to have nothing earlier.
+    /// If every receiver is closed, then this returns a DequeueError instead.
+    pub fn select(
+        &mut self,
+        manager: &TimeManager,
+    ) -> Result<(usize, ChannelElement<T>), DequeueError> {
+        self.select_from(manager, |_| true)
+    }
+
+    /// Like [ReceiverGroup::select], but only considers the receivers whose index satisfies `eligible`, leaving the others untouched.
+    /// If every eligible receiver is closed, then this returns a DequeueError instead.
+    pub fn select_from(
+        &mut self,
+        manager: &TimeManager,
+        eligible: impl Fn(usize) -> bool,
+    ) -> Result<(usize, ChannelElement<T>), DequeueError> {
+        loop {
+            let events: Vec<EventTime> = self
+                .receivers
+                .iter()
+                .zip(self.closed.iter())
+                .enumerate()
+                .map(|(ind, (recv, closed))| {
+                    if *closed || !eligible(ind) {
+                        EventTime::Closed
+                    } else {
+                        recv.next_event()
+                    }
+                })
+                .collect();
+
+            let mut earliest_ready: Option<Time> = None;
+            let mut horizon: Option<Time> = None;
+            for (ind, event) in events.iter().enumerate() {
+                match event {
+                    EventTime::Ready(time) => {
+                        earliest_ready = Some(earliest_ready.map_or(*time, |t| t.min(*time)))
+                    }
+                    EventTime::Nothing(time) => {
+                        horizon = Some(horizon.map_or(*time, |t| t.min(*time)))
+                    }
+                    EventTime::Closed if eligible(ind) => self.closed[ind] = true,
+                    EventTime::Closed => {}
+                }
+            }
+
+            match (earliest_ready, horizon) {
+                // Every open receiver is known to have nothing before this element.
+                (Some(ready), None) => return self.dequeue_at(manager, &events, ready),
+                (Some(ready), Some(nothing)) if ready <= nothing => {
+                    return self.dequeue_at(manager, &events, ready)
+                }
+                // Some receiver may still produce an earlier element, so wait on it.
+                (_, Some(nothing)) => manager.advance(nothing + 1),
+                (None, None) => return Err(DequeueError::Closed),
+            }
+        }
+    }
+
+    fn dequeue_at(
+        &mut self,
+        manager: &TimeManager,
+        events: &[EventTime],
+        time: Time,
+    ) -> Result<(usize, ChannelElement<T>), DequeueError> {
+        let candidates: Vec<usize> = events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| **event == EventTime::Ready(time))
+            .map(|(ind, _)| ind)
+            .collect();
+
+        let index = match self.tie_break {
+            TieBreak::Lowest => candidates[0],
+            TieBreak::Highest => *candidates.last().unwrap(),
+            TieBreak::RoundRobin => *candidates
+                .iter()
+                .find(|ind| **ind >= self.next_index)
+                .unwrap_or(&candidates[0]),
+        };
+        self.next_index = (index + 1) % self.receivers.len();
+
+        manager.advance(time);
+        self.receivers[index]
+            .dequeue(manager)
+            .map(|element| (index, element))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReceiverGroup, TieBreak};
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    fn run_group_test(tie_break: TieBreak, expected: Vec<(usize, u64)>) {
+        const NUM_INPUTS: usize = 3;
+        const NUM_ELEMENTS: u64 = 4;
+        let mut parent = ProgramBuilder::default();
+
+        let mut receivers = vec![];
+        for _ in 0..NUM_INPUTS {
+            let (snd, rcv) = parent.unbounded();
+            receivers.push(rcv);
+            let mut sender = FunctionContext::default();
+            snd.attach_sender(&sender);
+            // Every element is ready at the same tick, so the order is decided entirely by the tie break.
+            sender.set_run(move |time| {
+                for iter in 0..NUM_ELEMENTS {
+                    snd.enqueue(time, ChannelElement::new(Time::new(1), iter))
+                        .unwrap();
+                }
+            });
+            parent.add_child(sender);
+        }
+
+        let mut group = ReceiverGroup::new(receivers, tie_break);
+        let mut consumer = FunctionContext::default();
+        group.attach_receiver(&consumer);
+        consumer.set_run(move |time| {
+            let mut selected = vec![];
+            while let Ok((ind, element)) = group.select(time) {
+                assert_eq!(element.time, Time::new(1));
+                selected.push((ind, element.data));
+            }
+            assert!(group.all_closed());
+            assert_eq!(selected, expected);
+        });
+        parent.add_child(consumer);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_select_skewed() {
+        // Each sender produces random timestamps at its own pace, with the first lagging behind in wall-clock time.
+        // Elements must still come out in strict timestamp order, with ties going to the lowest index.
+        const NUM_INPUTS: usize = 4;
+        const NUM_ELEMENTS: usize = 32;
+        let mut parent = ProgramBuilder::default();
+        let mut rng = fastrand::Rng::with_seed(0x6e0);
+
+        let mut receivers = vec![];
+        let mut expected = vec![];
+        for ind in 0..NUM_INPUTS {
+            let (snd, rcv) = parent.unbounded();
+            receivers.push(rcv);
+            let mut times: Vec<u64> = (0..NUM_ELEMENTS).map(|_| rng.u64(1..200)).collect();
+            times.sort();
+            expected.extend(times.iter().map(|time| (*time, ind)));
+
+            let mut sender = FunctionContext::default();
+            snd.attach_sender(&sender);
+            sender.set_run(move |time| {
+                for element_time in times {
+                    if ind == 0 {
+                        crate::shim::sleep(std::time::Duration::from_millis(1));
+                    }
+                    snd.enqueue(time, ChannelElement::new(Time::new(element_time), ind))
+                        .unwrap();
+                }
+            });
+            parent.add_child(sender);
+        }
+        expected.sort();
+
+        let mut group = ReceiverGroup::new(receivers, TieBreak::Lowest);
+        let mut consumer = FunctionContext::default();
+        group.attach_receiver(&consumer);
+        consumer.set_run(move |time| {
+            let mut selected = vec![];
+            while let Ok((ind, element)) = group.select(time) {
+                assert_eq!(element.data, ind);
+                selected.push((element.time.time(), ind));
+            }
+            assert_eq!(selected, expected);
+        });
+        parent.add_child(consumer);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_select_lowest() {
+        run_group_test(
+            TieBreak::Lowest,
+            (0..3).flat_map(|i| (0..4).map(move |t| (i, t))).collect(),
+        );
+    }
+
+    #[test]
+    fn test_select_highest() {
+        run_group_test(
+            TieBreak::Highest,
+            (0..3)
+                .rev()
+                .flat_map(|i| (0..4).map(move |t| (i, t)))
+                .collect(),
+        );
+    }
+
+    #[test]
+    fn test_select_round_robin() {
+        run_group_test(
+            TieBreak::RoundRobin,
+            (0..4).flat_map(|t| (0..3).map(move |i| (i, t))).collect(),
+        );
+    }
+}
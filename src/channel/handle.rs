@@ -4,7 +4,7 @@
 
 use std::sync::Arc;
 
-use crate::shim::channel;
+use crate::shim::{channel, Mutex};
 
 use crate::datastructures::{sync_unsafe::SyncUnsafeCell, Identifier, Time};
 
@@ -33,6 +33,12 @@ pub(crate) struct ChannelData<T: Clone> {
     sender: SyncUnsafeCell<SenderImpl<T>>,
     receiver: SyncUnsafeCell<ReceiverImpl<T>>,
     channel_spec: Arc<ChannelSpec>,
+
+    // Tokens which are placed into the channel when it is initialized.
+    initial_tokens: Mutex<Vec<ChannelElement<T>>>,
+
+    // Times of the injected tokens, held until the sender's context is running and can log them.
+    unlogged_tokens: crossbeam::queue::SegQueue<Time>,
 }
 
 impl<T: Clone> ChannelData<T> {
@@ -41,9 +47,29 @@ impl<T: Clone> ChannelData<T> {
             sender: SyncUnsafeCell::new(UninitializedSender::new(spec.clone()).into()),
             receiver: SyncUnsafeCell::new(UninitializedReceiver::new(spec.clone()).into()),
             channel_spec: spec,
+            initial_tokens: Mutex::new(vec![]),
+            unlogged_tokens: crossbeam::queue::SegQueue::new(),
+        }
+    }
+
+    /// Queues up tokens to be placed into the channel on initialization.
+    /// On failure, returns the capacity of the channel.
+    pub(crate) fn push_initial_tokens(&self, tokens: Vec<ChannelElement<T>>) -> Result<(), usize> {
+        let mut initial_tokens = self.initial_tokens.lock().unwrap();
+        match self.channel_spec.capacity() {
+            Some(capacity) if initial_tokens.len() + tokens.len() > capacity => Err(capacity),
+            _ => {
+                initial_tokens.extend(tokens);
+                Ok(())
+            }
         }
     }
 
+    /// Takes the time of the next injected token which has yet to be logged.
+    pub(super) fn next_unlogged_token(&self) -> Option<Time> {
+        self.unlogged_tokens.pop()
+    }
+
     #[allow(clippy::mut_from_ref)]
     pub(super) fn sender(&self) -> &mut SenderImpl<T> {
         unsafe { self.sender.get().as_mut().unwrap() }
@@ -66,6 +92,16 @@ impl<T: Clone> ChannelHandle for ChannelData<T> {
             spec: self.channel_spec.make_inline(),
             underlying,
         };
+        let initial_tokens = std::mem::take(&mut *self.initial_tokens.lock().unwrap());
+        let num_tokens = initial_tokens.len();
+        initial_tokens
+            .iter()
+            .for_each(|token| self.unlogged_tokens.push(token.time));
+        let inject = |tx: &channel::Sender<ChannelElement<T>>| {
+            initial_tokens
+                .into_iter()
+                .for_each(|token| tx.send(token).unwrap())
+        };
         match self.channel_spec.capacity() {
             Some(capacity) => {
                 cfg_if::cfg_if! {
@@ -79,14 +115,16 @@ impl<T: Clone> ChannelHandle for ChannelData<T> {
                         let (resp_t, resp_r) = channel::bounded::<Time>(capacity);
                     }
                 }
+                inject(&tx);
                 match flavor {
                     ChannelFlavor::Acyclic => {
                         *self.sender() = BoundedAcyclicSender {
                             data: make_sender_data(tx),
                             bound: BoundedData {
                                 resp: resp_r,
-                                send_receive_delta: 0,
+                                send_receive_delta: num_tokens,
                             },
+                            pending_ack: None,
                         }
                         .into();
                         *self.receiver() = BoundedAcyclicReceiver {
@@ -100,7 +138,7 @@ impl<T: Clone> ChannelHandle for ChannelData<T> {
                             data: make_sender_data(tx),
                             bound: BoundedData {
                                 resp: resp_r,
-                                send_receive_delta: 0,
+                                send_receive_delta: num_tokens,
                             },
                             next_available: None,
                         }
@@ -117,11 +155,10 @@ impl<T: Clone> ChannelHandle for ChannelData<T> {
 
             // Unbounded channel
             None => {
-                //
+                let (snd, rcv) = channel::unbounded();
+                inject(&snd);
                 match flavor {
                     ChannelFlavor::Acyclic => {
-                        let (snd, rcv) = channel::unbounded();
-
                         *self.sender() = UnboundedSender {
                             data: make_sender_data(snd),
                         }
@@ -133,8 +170,6 @@ impl<T: Clone> ChannelHandle for ChannelData<T> {
                         .into();
                     }
                     ChannelFlavor::Cyclic => {
-                        let (snd, rcv) = channel::unbounded();
-
                         *self.sender() = UnboundedSender {
                             data: make_sender_data(snd),
                         }
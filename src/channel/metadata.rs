@@ -0,0 +1,120 @@
+use crate::types::DAMType;
+
+use super::ChannelElement;
+
+/// A channel payload carrying side-channel metadata (such as a bank address, QoS tag, or debug source) alongside its data.
+/// The metadata does not contribute to the size of the element.
+/// Channels carrying these payloads are built like any other, e.g. `builder.bounded::<WithMetadata<T, M>>(capacity)`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WithMetadata<T, M> {
+    /// The contained data
+    pub data: T,
+    /// The attached metadata
+    pub meta: M,
+}
+
+impl<T, M> DAMType for WithMetadata<T, M>
+where
+    T: DAMType,
+    M: Sync + Send + Default + core::fmt::Debug + Clone,
+{
+    fn dam_size(&self) -> usize {
+        self.data.dam_size()
+    }
+}
+
+/// A [ChannelElement] whose payload carries metadata.
+pub type MetaChannelElement<T, M> = ChannelElement<WithMetadata<T, M>>;
+
+impl<T> ChannelElement<T> {
+    /// Attaches metadata to the element, keeping its timestamp.
+    pub fn with_metadata<M>(self, meta: M) -> MetaChannelElement<T, M> {
+        ChannelElement {
+            time: self.time,
+            data: WithMetadata {
+                data: self.data,
+                meta,
+            },
+        }
+    }
+}
+
+impl<T, M> MetaChannelElement<T, M> {
+    /// Discards the metadata, keeping the timestamp and data.
+    pub fn strip_metadata(self) -> ChannelElement<T> {
+        self.split_metadata().0
+    }
+
+    /// Separates the element into a plain [ChannelElement] and its metadata.
+    pub fn split_metadata(self) -> (ChannelElement<T>, M) {
+        (
+            ChannelElement {
+                time: self.time,
+                data: self.data.data,
+            },
+            self.data.meta,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        types::DAMType,
+        utility_contexts::FunctionContext,
+    };
+
+    use super::WithMetadata;
+
+    #[test]
+    fn test_metadata_round_trip() {
+        const NUM_ELEMENTS: u64 = 16;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded::<WithMetadata<u64, u32>>(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                let element =
+                    ChannelElement::new(Time::new(iter), iter).with_metadata(iter as u32 % 4);
+                snd.enqueue(time, element).unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                let element = rcv.dequeue(time).unwrap();
+                // The metadata is free to carry.
+                assert_eq!(element.data.dam_size(), iter.dam_size());
+                let (element, bank) = element.split_metadata();
+                assert_eq!(element.data, iter);
+                assert_eq!(bank, iter as u32 % 4);
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_strip_metadata() {
+        let element = ChannelElement::new(Time::new(3), 7u64).with_metadata("source");
+        assert_eq!(
+            element.clone().strip_metadata(),
+            ChannelElement::new(Time::new(3), 7)
+        );
+        assert_eq!(element.data.meta, "source");
+    }
+}
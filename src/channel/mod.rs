@@ -7,7 +7,36 @@ mod channel_id;
 pub mod utils;
 pub use channel_id::*;
 
-mod events;
+pub(crate) mod events;
+
+mod metadata;
+pub use metadata::*;
+
+mod batch;
+pub use batch::BatchElement;
+
+mod either;
+pub use either::{Either, MuxSender, SplitReceiver};
+
+mod expiring;
+pub use expiring::{ExpiringElement, ExpiringReceiver};
+
+mod fault;
+pub use fault::{FaultDetector, FaultInjector, Faultable, Recv};
+
+mod observer;
+pub use observer::{ChannelEvent, ChannelObserver, MonitorID};
+
+mod tagged;
+pub use tagged::Tagged;
+
+mod stats;
+pub use stats::StallHistogram;
+
+#[cfg(feature = "compress")]
+mod compress;
+#[cfg(feature = "compress")]
+pub use compress::*;
 
 mod flavors;
 
@@ -16,11 +45,18 @@ pub(crate) use flavors::*;
 pub(crate) mod channel_spec;
 mod receiver;
 mod sender;
+pub use sender::bounded::SendOptions;
 
 pub(crate) mod handle;
 
 pub mod adapters;
 
+/// Selecting across multiple receivers of the same type.
+pub mod group;
+
+/// Streaming reductions over received elements.
+pub mod accumulate;
+
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -43,7 +79,9 @@ use self::sender::terminated::TerminatedSender;
 use self::sender::{SenderFlavor, SenderImpl};
 
 /// An item with an associated timestamp -- used for sending/receiving objects on channels and modifying contexts' owned times.
-#[derive(Clone, Debug)]
+/// Elements are ordered by time first, with ties broken by the data, so they can be used directly in priority structures.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChannelElement<T> {
     /// The element's timestamp
     pub time: Time,
@@ -63,6 +101,43 @@ impl<T> ChannelElement<T> {
         self.time = std::cmp::max(self.time, new_time);
     }
 
+    /// Updates the timestamp with an earlier timestamp, the counterpart to [ChannelElement::update_time].
+    /// This is useful for tracking the earliest deadline of a set of elements.
+    pub fn update_time_if_earlier(&mut self, new_time: Time) {
+        self.time = std::cmp::min(self.time, new_time);
+    }
+
+    /// Replaces the timestamp, regardless of whether it is earlier or later.
+    pub fn with_time(mut self, time: Time) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Makes the timestamp relative to `baseline`, saturating at zero. Infinite timestamps are left unchanged.
+    pub fn normalize_time(self, baseline: Time) -> Self {
+        let time = Self::normalized(self.time, baseline);
+        self.with_time(time)
+    }
+
+    /// Makes every timestamp in the batch relative to the earliest timestamp in the batch.
+    pub fn normalize_batch(elems: &mut [ChannelElement<T>]) {
+        if let Some(baseline) = elems.iter().map(|elem| elem.time).min() {
+            elems
+                .iter_mut()
+                .for_each(|elem| elem.time = Self::normalized(elem.time, baseline));
+        }
+    }
+
+    fn normalized(time: Time, baseline: Time) -> Time {
+        if time.is_infinite() {
+            time
+        } else if time <= baseline {
+            Time::new(0)
+        } else {
+            Time::new(time.time() - baseline.time())
+        }
+    }
+
     /// Converts between ChannelElement types, where the underlying types are compatible.
     /// We can't blanket implement this via From/Into because there are existing impls
     pub fn convert<U>(self) -> ChannelElement<U>
@@ -85,10 +160,56 @@ impl<T> ChannelElement<T> {
             data: self.data.try_into()?,
         })
     }
+
+    /// Returns whichever element has the earlier timestamp, preferring `a` on ties.
+    pub fn min_time(a: Self, b: Self) -> Self {
+        if b.time < a.time {
+            b
+        } else {
+            a
+        }
+    }
+
+    /// Returns whichever element has the later timestamp, preferring `a` on ties.
+    pub fn max_time(a: Self, b: Self) -> Self {
+        if b.time > a.time {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+impl<T: std::fmt::Display> ChannelElement<T> {
+    /// Formats a sequence of elements as a table with aligned `tick | data` columns, one element per row.
+    pub fn display_table(elems: &[ChannelElement<T>]) -> String {
+        let rows: Vec<(String, String)> = elems
+            .iter()
+            .map(|elem| (elem.time.to_string(), elem.data.to_string()))
+            .collect();
+        let tick_width = rows
+            .iter()
+            .map(|(tick, _)| tick.len())
+            .chain(std::iter::once("tick".len()))
+            .max()
+            .unwrap();
+        std::iter::once(("tick".to_string(), "data".to_string()))
+            .chain(rows)
+            .map(|(tick, data)| format!("{tick:>tick_width$} | {data}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for ChannelElement<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@tick {}: {}", self.time, self.data)
+    }
 }
 
 /// The result of a Peek operation
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum PeekResult<T> {
     /// We found an element. Note: The timestamp MAY be in the future.
     Something(ChannelElement<T>),
@@ -123,37 +244,227 @@ impl<T: DAMType> Sender<T> {
         self.underlying.id()
     }
 
+    /// Gets the namespaced ID of the channel, if it was created within a namespace.
+    pub fn namespaced_id(&self) -> Option<NamespacedChannelID> {
+        self.underlying.spec().namespaced_id()
+    }
+
+    /// Attaches a human-readable label to the channel, which is included in panic messages involving it.
+    pub fn set_debug_label(&self, label: &str) {
+        self.underlying.spec().set_debug_label(label);
+    }
+
+    /// Gets the channel's debug label, if one was set.
+    pub fn debug_label(&self) -> Option<Arc<str>> {
+        self.underlying.spec().debug_label()
+    }
+
+    /// Registers a callback which is invoked on every [ChannelEvent] of this channel.
+    /// Observers are shared between both ends of the channel, and run synchronously on the thread of whichever end caused the event.
+    pub fn add_observer(&self, observer: ChannelObserver) {
+        self.underlying.spec().add_observer(observer);
+    }
+
+    /// Registers a callback which fires whenever the channel's occupancy, as a fraction of its capacity, rises above `threshold`.
+    /// Occupancy is checked after each send, and the callback runs on the sender's thread.
+    /// It only fires again once the occupancy has dropped back to or below the threshold.
+    /// Only acknowledgements which have already reached the sender's current time are counted, so the occupancy may lag behind the receiver.
+    /// Panics on unbounded channels.
+    pub fn monitor_occupancy(
+        &self,
+        threshold: f64,
+        callback: Box<dyn Fn(f64) + Send>,
+    ) -> MonitorID {
+        let spec = self.underlying.spec();
+        assert!(
+            spec.capacity().is_some(),
+            "{}: Only bounded channels can be monitored for occupancy",
+            spec.describe()
+        );
+        spec.add_monitor(threshold, callback)
+    }
+
+    /// Removes a monitor registered with [Sender::monitor_occupancy], returning whether it was found.
+    pub fn remove_monitor(&self, id: MonitorID) -> bool {
+        self.underlying.spec().remove_monitor(id)
+    }
+
     /// Registers a context for the sender.
     pub fn attach_sender(&self, sender: &dyn Context) {
         // log_event(&{SendEvent::AttachSender(self.id, sender.id())});
         if let SenderImpl::Uninitialized(uninit) = self.under() {
             uninit.attach_sender(sender);
         } else {
-            panic!("Cannot attach a context to an initialized sender!");
+            panic!(
+                "{}: Cannot attach a context to an initialized sender!",
+                self.underlying.spec().describe()
+            );
         }
     }
 
+    /// Registers a context for the sender, and labels the channel with the context's name and ID.
+    /// If both ends of a channel are annotated, the label of whichever was annotated last is kept.
+    pub fn annotate_with_context(&self, sender: &dyn Context) {
+        self.attach_sender(sender);
+        self.set_debug_label(&format!("{}({})", sender.name(), sender.id()));
+    }
+
     /// Writes to a channel. This will error if the receive side has already been closed.
     pub fn enqueue(
         &self,
         manager: &TimeManager,
         data: ChannelElement<T>,
     ) -> Result<(), EnqueueError> {
+        if manager.budget_exhausted() || self.is_closed() {
+            return Err(EnqueueError::Closed);
+        }
+        if let Some(close_tick) = self.underlying.spec().close_tick() {
+            let send_time = data
+                .time
+                .max(manager.tick() + self.underlying.spec().latency());
+            if send_time > close_tick {
+                self.close();
+                return Err(EnqueueError::Closed);
+            }
+        }
+        let spec = self.underlying.spec();
+        if spec.has_observers() && self.under().remaining_capacity() == 0 {
+            spec.notify(ChannelEvent::Full { id: self.id() });
+        }
+        self.log_injected_tokens();
         log_event(&SendEvent::EnqueueStart(self.id())).unwrap();
+        let start = manager.tick();
         let res = self.under().enqueue(manager, data);
+        self.record_stall(start, manager.tick());
         log_event(&SendEvent::EnqueueFinish(self.id())).unwrap();
+        if res.is_ok() {
+            spec.notify(ChannelEvent::Sent {
+                id: self.id(),
+                time: manager.tick(),
+            });
+            if spec.has_monitors() {
+                let capacity = spec.capacity().unwrap();
+                let occupancy = capacity.saturating_sub(self.under().remaining_capacity());
+                spec.update_monitors(occupancy as f64 / capacity as f64);
+            }
+        }
         res
     }
 
+    /// Writes every element of an iterator to the channel in order, blocking whenever the channel is full.
+    /// Stops at the first error, such as the receiver having closed.
+    pub fn enqueue_iter(
+        &self,
+        manager: &TimeManager,
+        elements: impl IntoIterator<Item = ChannelElement<T>>,
+    ) -> Result<(), EnqueueError> {
+        elements
+            .into_iter()
+            .try_for_each(|element| self.enqueue(manager, element))
+    }
+
+    /// Whether this sender writes to a void channel, which discards everything sent to it.
+    /// Channels are only assigned their flavor on initialization, so this is always false beforehand.
+    pub fn is_void(&self) -> bool {
+        matches!(self.under(), SenderImpl::Void(_))
+    }
+
+    /// Writes to a channel like [Sender::enqueue], but returns immediately when the channel is void.
+    /// This is intended for contexts whose outputs are optionally connected.
+    pub fn enqueue_or_void(
+        &self,
+        manager: &TimeManager,
+        data: ChannelElement<T>,
+    ) -> Result<(), EnqueueError> {
+        if self.is_void() {
+            return Ok(());
+        }
+        self.enqueue(manager, data)
+    }
+
     /// Advances time forward until the channel is not full.
     pub fn wait_until_available(&self, manager: &TimeManager) -> Result<(), EnqueueError> {
-        self.under().wait_until_available(manager)
+        if manager.budget_exhausted() {
+            return Err(EnqueueError::Closed);
+        }
+        let start = manager.tick();
+        let res = self.under().wait_until_available(manager);
+        self.record_stall(start, manager.tick());
+        res
+    }
+
+    /// Returns how many more elements can currently be sent without blocking, or usize::MAX for unbounded channels.
+    /// Acknowledgements which the receiver has already sent and which are usable at the sender's current time are processed first, without blocking.
+    /// This is only a hint, as the receiver may free up more space by the time [Sender::enqueue] is called.
+    #[inline]
+    pub fn peek_remaining_capacity(&self) -> usize {
+        self.under().remaining_capacity()
+    }
+
+    /// Gets the distribution of stall durations this sender has experienced so far while waiting on a full channel.
+    pub fn stall_histogram(&self) -> StallHistogram {
+        self.underlying.spec().stall_histogram()
+    }
+
+    // Initial tokens are injected before any context is running, so they are logged by the first sender operation instead.
+    fn log_injected_tokens(&self) {
+        while let Some(time) = self.underlying.next_unlogged_token() {
+            log_event(&SendEvent::InjectToken(self.id(), time)).unwrap();
+        }
+    }
+
+    fn record_stall(&self, start: Time, end: Time) {
+        if !end.is_infinite() && end > start {
+            self.underlying
+                .spec()
+                .record_stall(end.time() - start.time());
+        }
+    }
+
+    /// Whether this sender has been closed, either explicitly or by [Sender::close_on_tick].
+    pub fn is_closed(&self) -> bool {
+        matches!(self.under(), SenderImpl::Terminated(_))
+    }
+
+    /// Closes the channel. The receiver observes the channel as closed once it has consumed every element already sent.
+    /// Closing an already closed sender does nothing, and further calls to [Sender::enqueue] return [EnqueueError::Closed].
+    pub fn close(&self) {
+        if self.is_closed() {
+            return;
+        }
+        self.log_injected_tokens();
+        log_event(&SendEvent::Cleanup(self.id())).unwrap();
+        *self.under() = TerminatedSender::new(self.underlying.spec()).into();
+        self.underlying
+            .spec()
+            .notify(ChannelEvent::Closed { id: self.id() });
+    }
+
+    /// Schedules the channel to close once it would carry an element past `tick`.
+    /// The check happens on each [Sender::enqueue]: an element which would arrive after `tick` closes the channel instead of being sent,
+    /// so the receiver observes the channel as closed right after the last element timestamped at or before `tick`.
+    pub fn close_on_tick(&self, tick: Time) {
+        self.underlying.spec().set_close_tick(tick);
+    }
+
+    /// Advances time until every element sent so far has been acknowledged by the receiver, and then closes the channel.
+    /// If the acknowledgements do not arrive within `timeout` ticks, the channel is left open and [CloseError::DrainTimeout] is returned instead.
+    pub fn close_gracefully(&self, manager: &TimeManager, timeout: u64) -> Result<(), CloseError> {
+        let deadline = Time::new(manager.tick().time().saturating_add(timeout));
+        self.under().wait_until_drained(manager, deadline)?;
+        self.close();
+        Ok(())
     }
 }
 
 impl<T: Clone> Drop for Sender<T> {
     fn drop(&mut self) {
-        *self.under() = TerminatedSender::default().into();
+        // Dropping an open sender also closes the channel, so observers still need to hear about it.
+        if !matches!(self.under(), SenderImpl::Terminated(_)) {
+            *self.under() = TerminatedSender::new(self.underlying.spec()).into();
+            let id = self.underlying.id();
+            self.underlying.spec().notify(ChannelEvent::Closed { id });
+        }
     }
 }
 
@@ -163,6 +474,39 @@ impl<T: Clone> Sender<T> {
     }
 }
 
+impl<T: DAMType> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spec = self.underlying.spec();
+        let mut result = f.debug_struct("Sender");
+        result.field("id", &format_args!("{}", self.id()));
+        if let Some(label) = spec.debug_label() {
+            result.field("name", &label);
+        }
+        let state = match self.under() {
+            SenderImpl::Cyclic(_) | SenderImpl::Acyclic(_) => None,
+            SenderImpl::Infinite(_) => Some("unbounded"),
+            SenderImpl::Void(_) => Some("void"),
+            SenderImpl::Uninitialized(_) => Some("uninitialized"),
+            SenderImpl::Terminated(_) => Some("closed"),
+        };
+        match state {
+            Some(state) => result.field("state", &format_args!("{state}")),
+            None => {
+                let capacity = spec.capacity().unwrap();
+                let occupancy = capacity - self.peek_remaining_capacity();
+                result.field("occupancy", &format_args!("{occupancy}/{capacity}"))
+            }
+        };
+        result.finish()
+    }
+}
+
+impl<T: DAMType> std::fmt::Display for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sender({})", self.underlying.spec().describe())
+    }
+}
+
 /// The receive side of a channel, modelled after std::mpsc, crossbeam, and the like.
 pub struct Receiver<T: Clone> {
     pub(crate) underlying: Arc<ChannelData<T>>,
@@ -174,16 +518,50 @@ impl<T: DAMType> Receiver<T> {
         self.underlying.id()
     }
 
+    /// Gets the namespaced ID of the channel, if it was created within a namespace.
+    pub fn namespaced_id(&self) -> Option<NamespacedChannelID> {
+        self.underlying.spec().namespaced_id()
+    }
+
+    /// Attaches a human-readable label to the channel. See [Sender::set_debug_label].
+    pub fn set_debug_label(&self, label: &str) {
+        self.underlying.spec().set_debug_label(label);
+    }
+
+    /// Gets the channel's debug label, if one was set. See [Sender::set_debug_label].
+    pub fn debug_label(&self) -> Option<Arc<str>> {
+        self.underlying.spec().debug_label()
+    }
+
+    /// The number of elements which have been dequeued from the channel so far.
+    pub fn message_count(&self) -> u64 {
+        self.underlying.spec().message_count()
+    }
+
+    /// Registers a callback which is invoked on every [ChannelEvent] of this channel. See [Sender::add_observer].
+    pub fn add_observer(&self, observer: ChannelObserver) {
+        self.underlying.spec().add_observer(observer);
+    }
+
     /// Registers a context for the receiver.
     pub fn attach_receiver(&self, receiver: &dyn Context) {
         log_event(&ReceiverEvent::AttachReceiver(self.id(), receiver.id())).unwrap();
         if let ReceiverImpl::Uninitialized(recv) = self.under() {
             recv.attach_receiver(receiver);
         } else {
-            panic!("Should not be able to attach a context to an initialized receiver")
+            panic!(
+                "{}: Should not be able to attach a context to an initialized receiver",
+                self.underlying.spec().describe()
+            )
         }
     }
 
+    /// Registers a context for the receiver, and labels the channel with the context's name and ID. See [Sender::annotate_with_context].
+    pub fn annotate_with_context(&self, receiver: &dyn Context) {
+        self.attach_receiver(receiver);
+        self.set_debug_label(&format!("{}({})", receiver.name(), receiver.id()));
+    }
+
     /// Peeks the channel. Note: It is possible to see a value in the future when peeking, as noted by [PeekResult].
     pub fn peek(&self) -> PeekResult<T> {
         log_event(&ReceiverEvent::Peek(self.id())).unwrap();
@@ -193,6 +571,9 @@ impl<T: DAMType> Receiver<T> {
     /// Advances forward in time until there is an element in the channel, and returns that value.
     /// If the channel is closed before another element is sent, then it returns a DequeueError instead.
     pub fn peek_next(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        if manager.budget_exhausted() {
+            return Err(DequeueError::Closed);
+        }
         log_event(&ReceiverEvent::PeekNextStart(self.id())).unwrap();
         let result = self.under().peek_next(manager);
         log_event(&ReceiverEvent::PeekNextFinish(self.id())).unwrap();
@@ -202,22 +583,274 @@ impl<T: DAMType> Receiver<T> {
     /// Advances forward in time until there is an element in the channel, and pops that value.
     /// If the channel is closed before another element is sent, then it returns a DequeueError instead.
     pub fn dequeue(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        if manager.budget_exhausted() {
+            return Err(DequeueError::Closed);
+        }
+        let spec = self.underlying.spec();
+        if spec.has_observers() && matches!(self.under().peek(), PeekResult::Nothing(_)) {
+            spec.notify(ChannelEvent::Empty { id: self.id() });
+        }
         log_event(&ReceiverEvent::DequeueStart(self.id())).unwrap();
         let result = self.under().dequeue(manager);
         log_event(&ReceiverEvent::DequeueFinish(self.id())).unwrap();
+        if let Ok(element) = &result {
+            spec.record_message();
+            spec.notify(ChannelEvent::Received {
+                id: self.id(),
+                time: element.time,
+            });
+        }
         result
     }
+
+    /// Dequeues an element like [Receiver::dequeue], and then applies a transformation to its data, keeping the timestamp.
+    /// The element is acknowledged before the transformation runs, so a slow transformation does not hold up the sender.
+    pub fn recv_with_transform<U>(
+        &self,
+        manager: &TimeManager,
+        f: impl FnOnce(T) -> U,
+    ) -> Result<ChannelElement<U>, DequeueError> {
+        let ChannelElement { time, data } = self.dequeue(manager)?;
+        Ok(ChannelElement::new(time, f(data)))
+    }
+
+    /// An alias for [Receiver::recv_with_transform], named after [Receiver::dequeue].
+    pub fn dequeue_map<U>(
+        &self,
+        manager: &TimeManager,
+        f: impl FnOnce(T) -> U,
+    ) -> Result<ChannelElement<U>, DequeueError> {
+        self.recv_with_transform(manager, f)
+    }
+
+    /// Waits until both this channel and `other` have an element, and dequeues them as a pair.
+    /// The pair is timestamped with the later of the two elements, and the receiver's clock only advances as far as that time.
+    /// If either channel is closed before producing an element, then it returns a DequeueError and neither element is consumed.
+    pub fn zip_with<U: DAMType>(
+        &self,
+        other: &Receiver<U>,
+        manager: &TimeManager,
+    ) -> Result<ChannelElement<(T, U)>, DequeueError> {
+        self.peek_next(manager)?;
+        other.peek_next(manager)?;
+        let left = self.dequeue(manager)?;
+        let right = other.dequeue(manager)?;
+        Ok(ChannelElement::new(
+            left.time.max(right.time),
+            (left.data, right.data),
+        ))
+    }
+
+    /// Advances forward in time until there is an element in the channel, and discards it.
+    /// This behaves like [Receiver::dequeue], including acknowledging the element, but makes the intent to drop the value explicit.
+    pub fn advance_head(&self, manager: &TimeManager) -> Result<(), DequeueError> {
+        self.dequeue(manager).map(|_| ())
+    }
+
+    /// Discards the next `n` elements of the channel.
+    /// If the channel is closed before `n` elements were discarded, then it returns a DequeueError instead.
+    pub fn advance_n(&self, n: usize, manager: &TimeManager) -> Result<(), DequeueError> {
+        for _ in 0..n {
+            self.advance_head(manager)?;
+        }
+        Ok(())
+    }
+
+    /// Discards every element timestamped before `time`, acknowledging each one, and returns how many were discarded.
+    /// This stops as soon as the channel is known to have nothing else before `time`, without waiting for later elements.
+    /// The receiver's clock is advanced as elements are discarded, but never past `time`.
+    pub fn skip_until(&self, time: Time, manager: &TimeManager) -> usize {
+        let mut skipped = 0;
+        loop {
+            match self.peek() {
+                PeekResult::Something(element) if element.time < time => {
+                    if self.advance_head(manager).is_err() {
+                        return skipped;
+                    }
+                    skipped += 1;
+                }
+                PeekResult::Something(_) | PeekResult::Closed => return skipped,
+                PeekResult::Nothing(horizon) if horizon >= time => return skipped,
+                PeekResult::Nothing(horizon) => manager.advance(horizon + 1),
+            }
+        }
+    }
+
+    /// Dequeues every element until the channel closes, combining them into an accumulator, analogous to [Iterator::fold].
+    pub fn fold<Acc>(
+        &self,
+        initial: Acc,
+        mut f: impl FnMut(Acc, ChannelElement<T>) -> Acc,
+        manager: &TimeManager,
+    ) -> Result<Acc, DequeueError> {
+        let mut acc = initial;
+        loop {
+            match self.dequeue(manager) {
+                Ok(element) => acc = f(acc, element),
+                Err(DequeueError::Closed) => return Ok(acc),
+            }
+        }
+    }
+
+    /// Dequeues every element until the channel closes, returning how many there were.
+    /// Elements are dropped as soon as they are dequeued, so nothing is kept in memory.
+    pub fn count_until_closed(&self, manager: &TimeManager) -> u64 {
+        let mut count = 0;
+        while self.dequeue(manager).is_ok() {
+            count += 1;
+        }
+        count
+    }
+
+    /// Like [Receiver::count_until_closed], but gives up once `timeout` of wall-clock time has passed.
+    /// Each wait on the sender is bounded by the remaining time, so a stalled sender cannot hang the count.
+    pub fn count_until_closed_with_timeout(
+        &self,
+        manager: &TimeManager,
+        timeout: std::time::Duration,
+    ) -> Result<u64, CountTimeout> {
+        let deadline = std::time::Instant::now() + timeout;
+        let spec = self.underlying.spec();
+        let mut count = 0;
+        loop {
+            // Once the sender has caught up, peeking and dequeueing no longer block.
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            if remaining.is_zero()
+                || spec
+                    .try_wait_until_sender(manager.tick(), remaining)
+                    .is_none()
+            {
+                return Err(CountTimeout { count });
+            }
+            match self.peek() {
+                PeekResult::Something(_) => {
+                    self.dequeue(manager).unwrap();
+                    count += 1;
+                }
+                PeekResult::Nothing(time) => manager.advance(time + 1),
+                PeekResult::Closed => return Ok(count),
+            }
+        }
+    }
+
+    /// Lazily dequeues elements, yielding the accumulator after each one, analogous to [Iterator::scan].
+    /// The iterator ends once the channel closes.
+    pub fn scan<'a, Acc: Clone + 'a>(
+        &'a self,
+        initial: Acc,
+        mut f: impl FnMut(Acc, ChannelElement<T>) -> Acc + 'a,
+        manager: &'a TimeManager,
+    ) -> impl Iterator<Item = Acc> + 'a {
+        let mut acc = Some(initial);
+        std::iter::from_fn(move || {
+            let element = self.dequeue(manager).ok()?;
+            let next = f(acc.take()?, element);
+            acc = Some(next.clone());
+            Some(next)
+        })
+    }
+
+    /// Converts the receiver into an iterator which dequeues with the given time manager, ending once the channel closes.
+    /// This allows using the standard iterator adapters, e.g. `receiver.into_iter_simulation(time).filter(|e| e.time > t).count()`.
+    pub fn into_iter_simulation(self, manager: &TimeManager) -> SimulationIter<'_, T> {
+        SimulationIter {
+            receiver: self,
+            manager,
+        }
+    }
+
+    /// Gets the time of the next element if it has already arrived, without blocking, advancing time, or consuming it.
+    /// Unlike [Receiver::peek], this never waits on the sender, so `None` only means that nothing is known to be available yet.
+    pub fn first_available_time(&self) -> Option<Time> {
+        self.under().first_available_time()
+    }
+
+    /// Checks whether an element has already arrived, without blocking or advancing time.
+    /// This is a cheaper alternative to [Receiver::peek] for polling loops, as it never waits on the sender.
+    pub fn has_pending(&self) -> bool {
+        self.first_available_time().is_some()
+    }
+
+    /// Consumes the receiver, splitting off the element which has already been fetched into the head (if any).
+    /// This never blocks or reads further from the channel, so the head is only populated after a [Receiver::peek] or [Receiver::peek_next].
+    /// The extracted element counts as dequeued, and is acknowledged at the later of its time and the receiver's current time.
+    pub fn take_head(self) -> (Option<ChannelElement<T>>, Receiver<T>) {
+        let head = self.under().take_head();
+        if head.is_some() {
+            self.underlying.spec().record_message();
+        }
+        (head, self)
+    }
+}
+
+/// An iterator over the elements of a channel, created by [Receiver::into_iter_simulation].
+pub struct SimulationIter<'a, T: Clone> {
+    receiver: Receiver<T>,
+    manager: &'a TimeManager,
+}
+
+impl<T: Clone> SimulationIter<'_, T> {
+    /// Unwraps the underlying receiver, for instance to keep using it after stopping iteration early.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.receiver
+    }
+}
+
+impl<T: DAMType> Iterator for SimulationIter<'_, T> {
+    type Item = ChannelElement<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.dequeue(self.manager).ok()
+    }
 }
 
 impl<T: Clone> Receiver<T> {
     fn under(&self) -> &mut ReceiverImpl<T> {
         self.underlying.receiver()
     }
+
+    /// Consumes the element which has already been fetched into the head, if any, without blocking.
+    fn discard_head(&self) {
+        if self.under().take_head().is_some() {
+            self.underlying.spec().record_message();
+        }
+    }
+}
+
+impl<T: DAMType> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spec = self.underlying.spec();
+        let mut result = f.debug_struct("Receiver");
+        result.field("id", &format_args!("{}", self.id()));
+        if let Some(label) = spec.debug_label() {
+            result.field("name", &label);
+        }
+        result.field("received", &spec.message_count());
+        let state = match self.under() {
+            ReceiverImpl::Uninitialized(_) => Some("uninitialized"),
+            ReceiverImpl::Terminated(_) => Some("closed"),
+            _ => None,
+        };
+        match state {
+            Some(state) => result.field("state", &format_args!("{state}")),
+            None => match self.first_available_time() {
+                Some(time) => result.field("head", &format_args!("Ready({time})")),
+                None => result.field("head", &format_args!("Empty")),
+            },
+        };
+        result.finish()
+    }
+}
+
+impl<T: DAMType> std::fmt::Display for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Receiver({})", self.underlying.spec().describe())
+    }
 }
 
 impl<T: Clone> Drop for Receiver<T> {
     fn drop(&mut self) {
-        *self.under() = TerminatedReceiver::default().into();
+        *self.under() = TerminatedReceiver::new(self.underlying.spec()).into();
     }
 }
 
@@ -236,3 +869,19 @@ pub enum EnqueueError {
     #[error("Enqueued to a simulation-closed channel!")]
     Closed,
 }
+
+/// Errors that can occur when gracefully closing a channel.
+#[derive(Error, Debug)]
+pub enum CloseError {
+    /// The receiver did not acknowledge all outstanding elements before the deadline.
+    #[error("Timed out waiting for the receiver to acknowledge all elements!")]
+    DrainTimeout,
+}
+
+/// The wall-clock deadline passed before the channel was closed, see [Receiver::count_until_closed_with_timeout].
+#[derive(Error, Debug)]
+#[error("Timed out after counting {count} elements!")]
+pub struct CountTimeout {
+    /// The number of elements counted before the deadline
+    pub count: u64,
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::datastructures::Time;
+
+use super::ChannelID;
+
+/// Events which can be observed on a channel, see [super::Sender::add_observer].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelEvent {
+    /// An element was sent, at the sender's time after the send completed.
+    Sent {
+        /// The channel
+        id: ChannelID,
+        /// When the send completed
+        time: Time,
+    },
+
+    /// An element was received, timestamped with the element's time.
+    Received {
+        /// The channel
+        id: ChannelID,
+        /// The time of the received element
+        time: Time,
+    },
+
+    /// A sender found the channel full, and will have to wait for the receiver.
+    Full {
+        /// The channel
+        id: ChannelID,
+    },
+
+    /// A receiver found the channel empty, and will have to wait for the sender.
+    Empty {
+        /// The channel
+        id: ChannelID,
+    },
+
+    /// The sender closed the channel, either explicitly or by being dropped.
+    Closed {
+        /// The channel
+        id: ChannelID,
+    },
+}
+
+/// A callback which is invoked on each [ChannelEvent].
+pub type ChannelObserver = Box<dyn Fn(ChannelEvent) + Send + Sync>;
+
+/// Identifies a monitor registered with [super::Sender::monitor_occupancy], so that it can be removed later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MonitorID(pub(super) usize);
+
+/// A callback which fires when a channel's occupancy rises above a threshold.
+pub(crate) struct OccupancyMonitor {
+    pub(crate) id: MonitorID,
+    threshold: f64,
+    callback: Box<dyn Fn(f64) + Send>,
+    above: bool,
+}
+
+impl OccupancyMonitor {
+    pub(crate) fn new(id: MonitorID, threshold: f64, callback: Box<dyn Fn(f64) + Send>) -> Self {
+        Self {
+            id,
+            threshold,
+            callback,
+            above: false,
+        }
+    }
+
+    /// Invokes the callback if the occupancy has just risen above the threshold.
+    pub(crate) fn update(&mut self, occupancy: f64) {
+        let above = occupancy > self.threshold;
+        if above && !self.above {
+            (self.callback)(occupancy);
+        }
+        self.above = above;
+    }
+}
@@ -10,7 +10,13 @@ pub(super) trait CyclicReceiver<T: Clone>: ReceiverCommon<T> {
         loop {
             match self.peek() {
                 PeekResult::Nothing(time) => {
-                    assert!(manager.tick() < time + 1);
+                    assert!(
+                        manager.tick() < time + 1,
+                        "{}: receiver time {:?} is past the proof of nothing at {:?}",
+                        self.data().spec.describe(),
+                        manager.tick(),
+                        time
+                    );
                     manager.advance(time + 1)
                 } // Nothing here, so tick forward until there might be
                 PeekResult::Closed => return Err(DequeueError::Closed), // Channel is closed, so let the dequeuer know
@@ -18,6 +18,8 @@ pub(super) trait ReceiverFlavor<T> {
     fn peek(&mut self) -> PeekResult<T>;
     fn peek_next(&mut self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError>;
     fn dequeue(&mut self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError>;
+    fn take_head(&mut self) -> Option<ChannelElement<T>>;
+    fn first_available_time(&mut self) -> Option<Time>;
 }
 
 #[enum_dispatch]
@@ -34,12 +36,6 @@ pub(super) enum ReceiverImpl<T: Clone> {
     CyclicInfinite(InfiniteCyclicReceiver<T>),
 }
 
-impl<T: Clone> Default for ReceiverImpl<T> {
-    fn default() -> Self {
-        terminated::TerminatedReceiver::default().into()
-    }
-}
-
 macro_rules! RegisterReceiver {
     ($name: ident, $receiver_mode: ident) => {
         impl<T> DataProvider<T> for $name<T> {
@@ -68,6 +64,14 @@ macro_rules! RegisterReceiver {
             ) -> Result<ChannelElement<T>, DequeueError> {
                 $receiver_mode::dequeue(self, manager)
             }
+
+            fn take_head(&mut self) -> Option<ChannelElement<T>> {
+                ReceiverCommon::take_head(self)
+            }
+
+            fn first_available_time(&mut self) -> Option<Time> {
+                ReceiverCommon::first_available_time(self)
+            }
         }
     };
 }
@@ -112,6 +116,36 @@ trait ReceiverCommon<T: Clone>: Responsive + DataProvider<T> {
         self.data().head.clone().unwrap()
     }
 
+    fn take_head(&mut self) -> Option<ChannelElement<T>> {
+        match self.data().head.take() {
+            Some(PeekResult::Something(element)) => {
+                // The element has left the channel, so the sender needs to be told about it.
+                let recv_time = self.data().spec.receiver_tlb();
+                self.register_recv(element.time.max(recv_time));
+                Some(element)
+            }
+            // Closed heads are kept so that later operations still observe the closure.
+            head @ Some(PeekResult::Closed) => {
+                self.data().head = head;
+                None
+            }
+            None | Some(PeekResult::Nothing(_)) => None,
+        }
+    }
+
+    fn first_available_time(&mut self) -> Option<Time> {
+        // Buffering an element which has already arrived doesn't change what the receiver observes.
+        if let None | Some(PeekResult::Nothing(_)) = self.data().head {
+            if let Ok(data) = self.data().underlying.try_recv() {
+                self.data().head = Some(PeekResult::Something(data));
+            }
+        }
+        match &self.data().head {
+            Some(PeekResult::Something(element)) => Some(element.time),
+            _ => None,
+        }
+    }
+
     fn try_update_head(&mut self, nothing_time: Time) {
         self.data().head = match self.data().underlying.try_recv() {
             Ok(data) => Some(PeekResult::Something(data)),
@@ -1,25 +1,57 @@
+use std::sync::Arc;
+
 use crate::{
-    channel::{ChannelElement, DequeueError, PeekResult},
+    channel::{channel_spec::ChannelSpec, ChannelElement, DequeueError, PeekResult},
+    datastructures::Time,
     view::TimeManager,
 };
 
 use super::ReceiverFlavor;
 
-#[derive(Default)]
-pub(crate) struct TerminatedReceiver {}
+pub(crate) struct TerminatedReceiver {
+    // Kept so that panics can name the channel.
+    spec: Arc<ChannelSpec>,
+}
 
-impl TerminatedReceiver {}
+impl TerminatedReceiver {
+    pub fn new(spec: Arc<ChannelSpec>) -> Self {
+        Self { spec }
+    }
+}
 
 impl<T> ReceiverFlavor<T> for TerminatedReceiver {
     fn peek(&mut self) -> PeekResult<T> {
-        panic!("Calling peek on a terminated receiver");
+        panic!(
+            "{}: Calling peek on a terminated receiver",
+            self.spec.describe()
+        );
     }
 
     fn peek_next(&mut self, _manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
-        panic!("Calling peek_next on a terminated receiver");
+        panic!(
+            "{}: Calling peek_next on a terminated receiver",
+            self.spec.describe()
+        );
     }
 
     fn dequeue(&mut self, _manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
-        panic!("Calling dequeue on a terminated receiver");
+        panic!(
+            "{}: Calling dequeue on a terminated receiver",
+            self.spec.describe()
+        );
+    }
+
+    fn take_head(&mut self) -> Option<ChannelElement<T>> {
+        panic!(
+            "{}: Calling take_head on a terminated receiver",
+            self.spec.describe()
+        );
+    }
+
+    fn first_available_time(&mut self) -> Option<Time> {
+        panic!(
+            "{}: Calling first_available_time on a terminated receiver",
+            self.spec.describe()
+        );
     }
 }
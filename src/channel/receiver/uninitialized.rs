@@ -3,6 +3,7 @@ use std::sync::Arc;
 use crate::{
     channel::{channel_spec::ChannelSpec, ChannelElement, DequeueError, PeekResult},
     context::Context,
+    datastructures::Time,
     view::TimeManager,
 };
 
@@ -20,15 +21,38 @@ impl UninitializedReceiver {
 
 impl<T> ReceiverFlavor<T> for UninitializedReceiver {
     fn peek(&mut self) -> PeekResult<T> {
-        panic!("Calling peek on an uninitialized receiver");
+        panic!(
+            "{}: Calling peek on an uninitialized receiver",
+            self.spec.describe()
+        );
     }
 
     fn peek_next(&mut self, _manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
-        panic!("Calling peek_next on an uninitialized receiver");
+        panic!(
+            "{}: Calling peek_next on an uninitialized receiver",
+            self.spec.describe()
+        );
     }
 
     fn dequeue(&mut self, _manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
-        panic!("Calling dequeue on an uninitialized receiver");
+        panic!(
+            "{}: Calling dequeue on an uninitialized receiver",
+            self.spec.describe()
+        );
+    }
+
+    fn take_head(&mut self) -> Option<ChannelElement<T>> {
+        panic!(
+            "{}: Calling take_head on an uninitialized receiver",
+            self.spec.describe()
+        );
+    }
+
+    fn first_available_time(&mut self) -> Option<Time> {
+        panic!(
+            "{}: Calling first_available_time on an uninitialized receiver",
+            self.spec.describe()
+        );
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::shim::channel;
 use crate::{
-    channel::{ChannelElement, EnqueueError},
+    channel::{ChannelElement, CloseError, EnqueueError},
     datastructures::Time,
     view::TimeManager,
 };
@@ -15,6 +15,28 @@ pub(crate) struct BoundedData {
 pub(crate) struct BoundedAcyclicSender<T> {
     pub(crate) data: SenderData<T>,
     pub(crate) bound: BoundedData,
+    // An acknowledgement which was read early, but isn't usable until the sender reaches its time.
+    pub(crate) pending_ack: Option<Time>,
+}
+
+impl<T> BoundedAcyclicSender<T> {
+    /// Processes every acknowledgement which is already available and usable by `now`, without blocking.
+    fn process_acks(&mut self, now: Time) {
+        loop {
+            let ack = match self.pending_ack.take() {
+                Some(time) => time,
+                None => match self.bound.resp.try_recv() {
+                    Ok(time) => time,
+                    Err(_) => return,
+                },
+            };
+            if ack > now {
+                self.pending_ack = Some(ack);
+                return;
+            }
+            self.bound.send_receive_delta -= 1;
+        }
+    }
 }
 
 impl<T> DataProvider<T> for BoundedAcyclicSender<T> {
@@ -32,9 +54,14 @@ impl<T> BoundedProvider for BoundedAcyclicSender<T> {
         if self.bound.send_receive_delta < self.data.spec.capacity.unwrap() {
             return Ok(());
         }
-        match self.bound.resp.recv() {
+        match self
+            .pending_ack
+            .take()
+            .map_or_else(|| self.bound.resp.recv(), Ok)
+        {
             Ok(time) => {
                 manager.advance(time);
+                self.bound.send_receive_delta -= 1;
                 Ok(())
             }
             Err(_) => Err(EnqueueError::Closed),
@@ -48,6 +75,15 @@ impl<T> SenderFlavor<T> for BoundedAcyclicSender<T> {
         BoundedProvider::wait_until_available(self, manager)
     }
 
+    fn remaining_capacity(&mut self) -> usize {
+        self.process_acks(self.data.spec.sender_tlb());
+        self.data
+            .spec
+            .capacity
+            .unwrap()
+            .saturating_sub(self.bound.send_receive_delta)
+    }
+
     fn enqueue(
         &mut self,
         manager: &TimeManager,
@@ -55,12 +91,45 @@ impl<T> SenderFlavor<T> for BoundedAcyclicSender<T> {
     ) -> Result<(), EnqueueError> {
         SenderCommon::enqueue(self, manager, data)
     }
+
+    fn wait_until_drained(
+        &mut self,
+        manager: &TimeManager,
+        deadline: Time,
+    ) -> Result<(), CloseError> {
+        while self.bound.send_receive_delta > 0 {
+            match self
+                .pending_ack
+                .take()
+                .map_or_else(|| self.bound.resp.recv(), Ok)
+            {
+                Ok(time) if time <= deadline => {
+                    manager.advance(time);
+                    self.bound.send_receive_delta -= 1;
+                }
+                Ok(time) => {
+                    // The slot isn't free until the acknowledgement's time, so keep it for the next send.
+                    self.pending_ack = Some(time);
+                    manager.advance(deadline);
+                    return Err(CloseError::DrainTimeout);
+                }
+                // The receiver is gone, so there is nothing left to wait for.
+                Err(_) => return Ok(()),
+            }
+        }
+        Ok(())
+    }
 }
 
+/// When a sender may next make progress.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SendOptions {
+    /// The sender may send at the given time.
     AvailableAt(Time),
+    /// The sender cannot send yet, and should check back once the receiver has reached the given time.
     CheckBackAt(Time),
+    /// The sender will never be able to send, as the channel is closed.
     Never,
 }
 pub(crate) struct BoundedCyclicSender<T> {
@@ -80,7 +149,11 @@ impl<T> BoundedCyclicSender<T> {
         loop {
             match self.bound.resp.try_recv() {
                 Ok(time) if time <= send_time => {
-                    assert!(self.bound.send_receive_delta > 0);
+                    assert!(
+                        self.bound.send_receive_delta > 0,
+                        "{}: received an acknowledgement with nothing in flight",
+                        self.data.spec.describe()
+                    );
                     self.bound.send_receive_delta -= 1;
                     retval = true;
                 }
@@ -103,6 +176,29 @@ impl<T> BoundedCyclicSender<T> {
     }
 }
 
+impl<T> BoundedCyclicSender<T> {
+    /// Processes every acknowledgement which is already available and usable at the sender's current time, without blocking.
+    fn process_acks(&mut self) {
+        loop {
+            let now = self.data.spec.sender_tlb();
+            match self.next_available {
+                Some(SendOptions::AvailableAt(time)) if time <= now => {
+                    self.bound.send_receive_delta -= 1;
+                    self.next_available = None;
+                }
+                Some(SendOptions::CheckBackAt(time)) if time <= now => self.next_available = None,
+                Some(_) => return,
+                None => {
+                    self.update_srd();
+                    if self.next_available.is_none() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl<T> BoundedProvider for BoundedCyclicSender<T> {
     fn register_send(&mut self) {
         self.bound.send_receive_delta += 1;
@@ -158,6 +254,15 @@ impl<T> SenderFlavor<T> for BoundedCyclicSender<T> {
         BoundedProvider::wait_until_available(self, manager)
     }
 
+    fn remaining_capacity(&mut self) -> usize {
+        self.process_acks();
+        self.data
+            .spec
+            .capacity
+            .unwrap()
+            .saturating_sub(self.bound.send_receive_delta)
+    }
+
     fn enqueue(
         &mut self,
         manager: &TimeManager,
@@ -165,4 +270,48 @@ impl<T> SenderFlavor<T> for BoundedCyclicSender<T> {
     ) -> Result<(), EnqueueError> {
         SenderCommon::enqueue(self, manager, data)
     }
+
+    fn wait_until_drained(
+        &mut self,
+        manager: &TimeManager,
+        deadline: Time,
+    ) -> Result<(), CloseError> {
+        loop {
+            if self.bound.send_receive_delta == 0 {
+                return Ok(());
+            }
+            match self.next_available {
+                Some(SendOptions::AvailableAt(time) | SendOptions::CheckBackAt(time))
+                    if time > deadline =>
+                {
+                    manager.advance(deadline);
+                    return Err(CloseError::DrainTimeout);
+                }
+                Some(SendOptions::AvailableAt(time)) => {
+                    manager.advance(time);
+                    self.bound.send_receive_delta -= 1;
+                    self.next_available = None;
+                    continue;
+                }
+                // The receiver is gone, so there is nothing left to wait for.
+                Some(SendOptions::Never) => return Ok(()),
+                Some(SendOptions::CheckBackAt(time)) => {
+                    manager.advance(time);
+                    self.next_available = None;
+                }
+                None => {}
+            }
+
+            if self.update_srd() {
+                continue;
+            }
+
+            let new_time = self.data.spec.wait_until_receiver(manager.tick());
+            if !self.update_srd() {
+                self.next_available = Some(SendOptions::CheckBackAt(
+                    new_time + self.data.spec.response_latency,
+                ));
+            }
+        }
+    }
 }
@@ -1,13 +1,13 @@
 use enum_dispatch::enum_dispatch;
 
-use crate::view::TimeManager;
+use crate::{datastructures::Time, view::TimeManager};
 
 use self::{
     bounded::{BoundedAcyclicSender, BoundedCyclicSender},
     unbounded::UnboundedSender,
 };
 
-use super::{channel_spec::InlineSpec, ChannelElement, EnqueueError};
+use super::{channel_spec::InlineSpec, ChannelElement, CloseError, EnqueueError};
 
 pub(super) mod bounded;
 pub(super) mod terminated;
@@ -24,6 +24,16 @@ pub trait SenderFlavor<T> {
         manager: &TimeManager,
         data: ChannelElement<T>,
     ) -> Result<(), EnqueueError>;
+
+    /// How many more elements can be sent without blocking, counting every acknowledgement usable at the sender's current time.
+    fn remaining_capacity(&mut self) -> usize;
+
+    /// Waits until all sent elements have been acknowledged, giving up once the receiver is known to be past the deadline.
+    fn wait_until_drained(
+        &mut self,
+        manager: &TimeManager,
+        deadline: Time,
+    ) -> Result<(), CloseError>;
 }
 
 #[enum_dispatch]
@@ -39,12 +49,6 @@ pub(super) enum SenderImpl<T> {
     Infinite(UnboundedSender<T>),
 }
 
-impl<T: Clone> Default for SenderImpl<T> {
-    fn default() -> Self {
-        terminated::TerminatedSender::default().into()
-    }
-}
-
 pub(crate) struct SenderData<T> {
     pub(crate) spec: InlineSpec,
     pub(crate) underlying: crate::shim::channel::Sender<ChannelElement<T>>,
@@ -1,33 +1,62 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use crate::{
-    channel::{ChannelElement, EnqueueError},
+    channel::{channel_spec::ChannelSpec, ChannelElement, CloseError, EnqueueError},
+    datastructures::Time,
     view::TimeManager,
 };
 
 use super::SenderFlavor;
 
 pub(crate) struct TerminatedSender<T> {
+    // Kept so that panics can name the channel.
+    spec: Arc<ChannelSpec>,
     _marker: PhantomData<T>,
 }
+
+impl<T> TerminatedSender<T> {
+    pub fn new(spec: Arc<ChannelSpec>) -> Self {
+        Self {
+            spec,
+            _marker: Default::default(),
+        }
+    }
+}
+
 impl<T> SenderFlavor<T> for TerminatedSender<T> {
+    fn remaining_capacity(&mut self) -> usize {
+        panic!(
+            "{}: Attempting to query the capacity of a terminated sender.",
+            self.spec.describe()
+        );
+    }
+
     fn enqueue(
         &mut self,
         _manager: &TimeManager,
         _data: ChannelElement<T>,
     ) -> Result<(), EnqueueError> {
-        panic!("Attempting to enqueue to a terminated sender.");
+        panic!(
+            "{}: Attempting to enqueue to a terminated sender.",
+            self.spec.describe()
+        );
     }
 
     fn wait_until_available(&mut self, _manager: &TimeManager) -> Result<(), EnqueueError> {
-        panic!("Attempting to wait for a terminated sender.");
+        panic!(
+            "{}: Attempting to wait for a terminated sender.",
+            self.spec.describe()
+        );
     }
-}
 
-impl<T> Default for TerminatedSender<T> {
-    fn default() -> Self {
-        Self {
-            _marker: Default::default(),
-        }
+    fn wait_until_drained(
+        &mut self,
+        _manager: &TimeManager,
+        _deadline: Time,
+    ) -> Result<(), CloseError> {
+        panic!(
+            "{}: Attempting to drain a terminated sender.",
+            self.spec.describe()
+        );
     }
 }
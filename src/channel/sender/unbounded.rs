@@ -1,5 +1,6 @@
 use crate::{
-    channel::{ChannelElement, EnqueueError},
+    channel::{ChannelElement, CloseError, EnqueueError},
+    datastructures::Time,
     view::TimeManager,
 };
 
@@ -30,6 +31,10 @@ impl<T> SenderFlavor<T> for UnboundedSender<T> {
         BoundedProvider::wait_until_available(self, manager)
     }
 
+    fn remaining_capacity(&mut self) -> usize {
+        usize::MAX
+    }
+
     fn enqueue(
         &mut self,
         manager: &TimeManager,
@@ -37,4 +42,13 @@ impl<T> SenderFlavor<T> for UnboundedSender<T> {
     ) -> Result<(), EnqueueError> {
         SenderCommon::enqueue(self, manager, data)
     }
+
+    fn wait_until_drained(
+        &mut self,
+        _manager: &TimeManager,
+        _deadline: Time,
+    ) -> Result<(), CloseError> {
+        // Unbounded channels never track outstanding elements.
+        Ok(())
+    }
 }
@@ -1,8 +1,9 @@
 use std::{marker::PhantomData, sync::Arc};
 
 use crate::{
-    channel::{channel_spec::ChannelSpec, ChannelElement, EnqueueError},
+    channel::{channel_spec::ChannelSpec, ChannelElement, CloseError, EnqueueError},
     context::Context,
+    datastructures::Time,
     view::TimeManager,
 };
 
@@ -13,16 +14,40 @@ pub(crate) struct UninitializedSender<T> {
     spec: Arc<ChannelSpec>,
 }
 impl<T> SenderFlavor<T> for UninitializedSender<T> {
+    fn remaining_capacity(&mut self) -> usize {
+        panic!(
+            "{}: Calling remaining_capacity on an uninitialized sender",
+            self.spec.describe()
+        );
+    }
+
     fn enqueue(
         &mut self,
         _manager: &TimeManager,
         _data: ChannelElement<T>,
     ) -> Result<(), EnqueueError> {
-        panic!("Calling enqueue on an uninitialized sender");
+        panic!(
+            "{}: Calling enqueue on an uninitialized sender",
+            self.spec.describe()
+        );
     }
 
     fn wait_until_available(&mut self, _manager: &TimeManager) -> Result<(), EnqueueError> {
-        panic!("Calling wait_until_available on an uninitialized sender");
+        panic!(
+            "{}: Calling wait_until_available on an uninitialized sender",
+            self.spec.describe()
+        );
+    }
+
+    fn wait_until_drained(
+        &mut self,
+        _manager: &TimeManager,
+        _deadline: Time,
+    ) -> Result<(), CloseError> {
+        panic!(
+            "{}: Calling wait_until_drained on an uninitialized sender",
+            self.spec.describe()
+        );
     }
 }
 
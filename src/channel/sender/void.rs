@@ -1,7 +1,8 @@
 use std::marker::PhantomData;
 
 use crate::{
-    channel::{ChannelElement, EnqueueError},
+    channel::{ChannelElement, CloseError, EnqueueError},
+    datastructures::Time,
     view::TimeManager,
 };
 
@@ -21,6 +22,10 @@ impl<T> Default for VoidSender<T> {
 }
 
 impl<T> SenderFlavor<T> for VoidSender<T> {
+    fn remaining_capacity(&mut self) -> usize {
+        usize::MAX
+    }
+
     fn enqueue(
         &mut self,
         _manager: &TimeManager,
@@ -33,4 +38,13 @@ impl<T> SenderFlavor<T> for VoidSender<T> {
         // No-op
         Ok(())
     }
+
+    fn wait_until_drained(
+        &mut self,
+        _manager: &TimeManager,
+        _deadline: Time,
+    ) -> Result<(), CloseError> {
+        // No-op
+        Ok(())
+    }
 }
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+/// The distribution of consecutive stall durations (in ticks) experienced by a sender.
+/// Many short stalls usually point to a bandwidth problem, while a few long stalls point to a latency problem.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StallHistogram {
+    counts: HashMap<u64, u64>,
+}
+
+impl StallHistogram {
+    /// Records a single stall of the given duration. Zero-length stalls are ignored.
+    pub fn record(&mut self, duration: u64) {
+        if duration > 0 {
+            *self.counts.entry(duration).or_default() += 1;
+        }
+    }
+
+    /// The raw histogram, mapping stall duration to the number of stalls of that duration.
+    pub fn counts(&self) -> &HashMap<u64, u64> {
+        &self.counts
+    }
+
+    /// The total number of stalls recorded.
+    pub fn num_stalls(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// The total number of ticks spent stalled.
+    pub fn total_stall_time(&self) -> u64 {
+        self.counts
+            .iter()
+            .map(|(duration, count)| duration * count)
+            .sum()
+    }
+
+    /// The mean stall duration, or 0 if there were no stalls.
+    pub fn mean(&self) -> f64 {
+        match self.num_stalls() {
+            0 => 0.0,
+            stalls => self.total_stall_time() as f64 / stalls as f64,
+        }
+    }
+
+    /// The stall duration at the given percentile, where `p` is in the range [0, 100].
+    /// Returns 0 if there were no stalls.
+    pub fn percentile(&self, p: f64) -> u64 {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "Percentile must be in [0, 100], got {p}"
+        );
+        let total = self.num_stalls();
+        if total == 0 {
+            return 0;
+        }
+        let rank = ((p / 100.0) * total as f64).ceil().max(1.0) as u64;
+
+        let mut durations: Vec<_> = self.counts.iter().collect();
+        durations.sort_unstable();
+        let mut seen = 0;
+        for (duration, count) in durations {
+            seen += count;
+            if seen >= rank {
+                return *duration;
+            }
+        }
+        unreachable!("Rank {rank} exceeds the number of stalls {total}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StallHistogram;
+
+    #[test]
+    fn test_stall_histogram() {
+        let mut histogram = StallHistogram::default();
+        assert_eq!(histogram.percentile(50.0), 0);
+        assert_eq!(histogram.mean(), 0.0);
+
+        [1, 1, 1, 1, 0, 2, 2, 10]
+            .into_iter()
+            .for_each(|stall| histogram.record(stall));
+        assert_eq!(histogram.num_stalls(), 7);
+        assert_eq!(histogram.total_stall_time(), 18);
+        assert_eq!(histogram.percentile(0.0), 1);
+        assert_eq!(histogram.percentile(50.0), 1);
+        assert_eq!(histogram.percentile(75.0), 2);
+        assert_eq!(histogram.percentile(100.0), 10);
+        assert!((histogram.mean() - 18.0 / 7.0).abs() < 1e-9);
+    }
+}
@@ -120,14 +120,350 @@ where
     }
 }
 
+#[derive(Default)]
+struct MeterState {
+    // Circular buffer of (tick, count) pairs, indexed by tick modulo the window.
+    buckets: Vec<(u64, u64)>,
+    total: u64,
+    first_tick: Option<u64>,
+    last_tick: u64,
+    peak: f64,
+}
+
+/// Measures the throughput of a channel, in elements per tick over a sliding window of simulated time.
+/// Meters are created by wrapping an endpoint with [RateMeter::wrap_receiver] or [RateMeter::wrap_sender],
+/// and may be queried from any thread while the simulation is running.
+pub struct RateMeter {
+    window: u64,
+    state: crate::shim::Mutex<MeterState>,
+}
+
+impl RateMeter {
+    /// Constructs a meter with a window of `window_ticks` ticks.
+    pub fn new(window_ticks: u64) -> Self {
+        assert!(
+            window_ticks > 0,
+            "RateMeter windows must be at least one tick"
+        );
+        Self {
+            window: window_ticks,
+            state: crate::shim::Mutex::new(MeterState {
+                buckets: vec![(0, 0); window_ticks as usize],
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Wraps a receiver, counting each element as it is dequeued.
+    pub fn wrap_receiver<T: DAMType>(
+        receiver: Receiver<T>,
+        window_ticks: u64,
+    ) -> (MeteredReceiver<T>, Arc<RateMeter>) {
+        let meter = Arc::new(Self::new(window_ticks));
+        (
+            MeteredReceiver {
+                receiver,
+                meter: meter.clone(),
+            },
+            meter,
+        )
+    }
+
+    /// Wraps a sender, counting each element as it is enqueued.
+    pub fn wrap_sender<T: DAMType>(
+        sender: Sender<T>,
+        window_ticks: u64,
+    ) -> (MeteredSender<T>, Arc<RateMeter>) {
+        let meter = Arc::new(Self::new(window_ticks));
+        (
+            MeteredSender {
+                sender,
+                meter: meter.clone(),
+            },
+            meter,
+        )
+    }
+
+    /// Records an element at the given tick. Ticks are expected to be non-decreasing.
+    pub fn record(&self, tick: Time) {
+        let tick = tick.time();
+        let mut state = self.state.lock().unwrap();
+        let bucket = &mut state.buckets[(tick % self.window) as usize];
+        if bucket.0 == tick {
+            bucket.1 += 1;
+        } else {
+            *bucket = (tick, 1);
+        }
+        state.total += 1;
+        state.first_tick.get_or_insert(tick);
+        state.last_tick = tick;
+        let current = Self::window_rate(&state, self.window, tick);
+        if current > state.peak {
+            state.peak = current;
+        }
+    }
+
+    fn window_rate(state: &MeterState, window: u64, tick: u64) -> f64 {
+        let count: u64 = state
+            .buckets
+            .iter()
+            .filter(|(bucket_tick, _)| *bucket_tick <= tick && *bucket_tick + window > tick)
+            .map(|(_, count)| count)
+            .sum();
+        count as f64 / window as f64
+    }
+
+    /// The throughput in the window ending at (and including) the given tick.
+    /// Only the most recent window of history is retained, so earlier ticks are not meaningful.
+    pub fn throughput_at(&self, tick: Time) -> f64 {
+        Self::window_rate(&self.state.lock().unwrap(), self.window, tick.time())
+    }
+
+    /// The highest windowed throughput observed so far.
+    pub fn peak_throughput(&self) -> f64 {
+        self.state.lock().unwrap().peak
+    }
+
+    /// The throughput averaged over the whole span between the first and latest recorded elements.
+    pub fn average_throughput(&self) -> f64 {
+        let state = self.state.lock().unwrap();
+        match state.first_tick {
+            Some(first) => state.total as f64 / (state.last_tick - first + 1) as f64,
+            None => 0.0,
+        }
+    }
+
+    /// The total number of elements recorded.
+    pub fn total(&self) -> u64 {
+        self.state.lock().unwrap().total
+    }
+}
+
+/// A receiver which records each dequeued element into a [RateMeter].
+pub struct MeteredReceiver<T: Clone> {
+    receiver: Receiver<T>,
+    meter: Arc<RateMeter>,
+}
+
+impl<T: DAMType> MeteredReceiver<T> {
+    /// Unwraps the underlying receiver.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.receiver
+    }
+}
+
+impl<T: DAMType> adapters::RecvAdapter<T> for MeteredReceiver<T> {
+    fn attach_receiver(&self, ctx: &dyn Context) {
+        self.receiver.attach_receiver(ctx)
+    }
+
+    fn peek(&self) -> PeekResult<T> {
+        self.receiver.peek()
+    }
+
+    fn peek_next(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        self.receiver.peek_next(manager)
+    }
+
+    fn dequeue(&self, manager: &TimeManager) -> Result<ChannelElement<T>, DequeueError> {
+        let result = self.receiver.dequeue(manager);
+        if result.is_ok() {
+            self.meter.record(manager.tick());
+        }
+        result
+    }
+}
+
+/// A sender which records each enqueued element into a [RateMeter].
+pub struct MeteredSender<T: Clone> {
+    sender: Sender<T>,
+    meter: Arc<RateMeter>,
+}
+
+impl<T: DAMType> MeteredSender<T> {
+    /// Unwraps the underlying sender.
+    pub fn into_inner(self) -> Sender<T> {
+        self.sender
+    }
+}
+
+impl<T: DAMType> adapters::SendAdapter<T> for MeteredSender<T> {
+    fn attach_sender(&self, ctx: &dyn Context) {
+        self.sender.attach_sender(ctx)
+    }
+
+    fn enqueue(&self, manager: &TimeManager, data: ChannelElement<T>) -> Result<(), EnqueueError> {
+        self.sender.enqueue(manager, data)?;
+        self.meter.record(manager.tick());
+        Ok(())
+    }
+
+    fn wait_until_available(&self, manager: &TimeManager) -> Result<(), EnqueueError> {
+        self.sender.wait_until_available(manager)
+    }
+}
+
+/// Errors produced by [Demultiplexer::run_step].
+#[derive(thiserror::Error, Debug)]
+pub enum DemuxError {
+    /// The input channel was closed.
+    #[error(transparent)]
+    Dequeue(#[from] DequeueError),
+
+    /// The selected output channel was closed.
+    #[error(transparent)]
+    Enqueue(#[from] EnqueueError),
+
+    /// No output was registered for the key, and there is no default handler.
+    #[error("No output registered for key {0}")]
+    UnknownKey(String),
+}
+
+/// Routes each element of a single input to one of several outputs, chosen by a key extracted from the element.
+/// Elements whose key has no registered output are passed to the default handler, if one was set.
+pub struct Demultiplexer<T: Clone, K> {
+    input: Receiver<T>,
+    outputs: std::collections::HashMap<K, Sender<T>>,
+    key: fn(&T) -> K,
+    default_handler: Option<Box<dyn Fn(ChannelElement<T>) + Send + Sync>>,
+}
+
+impl<T: DAMType, K: std::hash::Hash + Eq + std::fmt::Debug> Demultiplexer<T, K> {
+    /// Constructs a demultiplexer with no outputs.
+    pub fn new(input: Receiver<T>, key: fn(&T) -> K) -> Self {
+        Self {
+            input,
+            outputs: Default::default(),
+            key,
+            default_handler: None,
+        }
+    }
+
+    /// Sets the handler for elements whose key has no registered output.
+    pub fn with_default_handler(
+        mut self,
+        handler: impl Fn(ChannelElement<T>) + Send + Sync + 'static,
+    ) -> Self {
+        self.default_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Routes elements with the given key to `output`, returning the previously registered output if there was one.
+    /// Outputs registered after [Demultiplexer::attach] must be attached to the context separately.
+    pub fn register_key(&mut self, key: K, output: Sender<T>) -> Option<Sender<T>> {
+        self.outputs.insert(key, output)
+    }
+
+    /// Attaches the input and every registered output to the context.
+    pub fn attach(&self, ctx: &dyn Context) {
+        self.input.attach_receiver(ctx);
+        self.outputs
+            .values()
+            .for_each(|output| output.attach_sender(ctx));
+    }
+
+    /// Dequeues a single element and forwards it, unchanged, to the output registered for its key.
+    pub fn run_step(&self, manager: &TimeManager) -> Result<(), DemuxError> {
+        let element = self.input.dequeue(manager)?;
+        let key = (self.key)(&element.data);
+        match (self.outputs.get(&key), &self.default_handler) {
+            (Some(output), _) => output.enqueue(manager, element).map_err(DemuxError::from),
+            (None, Some(handler)) => {
+                handler(element);
+                Ok(())
+            }
+            (None, None) => Err(DemuxError::UnknownKey(format!("{key:?}"))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
     use crate::{
+        channel::adapters::RecvAdapter,
         simulation::ProgramBuilder,
-        utility_contexts::{random_trace, FunctionContext, TraceContext},
+        utility_contexts::{
+            random_trace, CheckerContext, FunctionContext, GeneratorContext, TraceContext,
+        },
     };
 
-    use super::{EventTime, Peekable};
+    use super::{Demultiplexer, EventTime, Peekable, RateMeter};
+
+    #[test]
+    fn test_rate_meter() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(|| 0..1000u32, snd));
+
+        let (rcv, meter) = RateMeter::wrap_receiver(rcv, 100);
+        let query_meter = meter.clone();
+        let mut fc = FunctionContext::default();
+        rcv.attach_receiver(&fc);
+        fc.set_run(move |time| {
+            // Elements arrive one per tick: consume them as they arrive, and then once every three ticks.
+            for iter in 0..1000 {
+                if iter >= 500 {
+                    time.incr_cycles(3);
+                }
+                rcv.dequeue(time).unwrap();
+            }
+            // Queried mid-simulation, from inside of a context.
+            assert_eq!(query_meter.total(), 1000);
+            assert_eq!(time.tick().time(), 2000);
+        });
+        ctx.add_child(fc);
+        let executed = ctx
+            .initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+        assert!(executed.passed());
+
+        assert_eq!(meter.total(), 1000);
+        assert!((meter.peak_throughput() - 1.0).abs() < 1e-9);
+        assert!((meter.average_throughput() - 0.5).abs() < 1e-9);
+        let recent = meter.throughput_at(crate::structures::Time::new(2000));
+        assert!((recent - 0.34).abs() < 1e-9, "{recent}");
+    }
+
+    #[test]
+    fn test_demultiplexer() {
+        const NUM_ELEMENTS: u32 = 30;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(|| 0..NUM_ELEMENTS, snd));
+
+        let unrouted = Arc::new(AtomicU32::new(0));
+        let unrouted_handle = unrouted.clone();
+        let mut demux =
+            Demultiplexer::new(rcv, |x: &u32| x % 3).with_default_handler(move |element| {
+                assert_eq!(element.data % 3, 2);
+                unrouted_handle.fetch_add(1, Ordering::Relaxed);
+            });
+        for key in 0..2 {
+            let (out_snd, out_rcv) = ctx.unbounded();
+            assert!(demux.register_key(key, out_snd).is_none());
+            ctx.add_child(CheckerContext::new(
+                move || (0..NUM_ELEMENTS).filter(move |x| x % 3 == key),
+                out_rcv,
+            ));
+        }
+
+        let mut fc = FunctionContext::default();
+        demux.attach(&fc);
+        fc.set_run(move |time| while demux.run_step(time).is_ok() {});
+        ctx.add_child(fc);
+        let executed = ctx
+            .initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+        assert!(executed.passed());
+        assert_eq!(unrouted.load(Ordering::Relaxed), NUM_ELEMENTS / 3);
+    }
 
     /// Puts stuff in a channel and checks when it's available.
     #[test]
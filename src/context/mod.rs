@@ -7,8 +7,9 @@ use std::{
 };
 
 use crate::{
-    channel::ChannelID,
+    channel::{ChannelID, Receiver, Sender},
     datastructures::{Identifiable, Identifier, VerboseIdentifier},
+    types::DAMType,
     view::TimeViewable,
 };
 
@@ -88,6 +89,22 @@ pub trait Context: Send + Sync + TimeViewable + Identifiable {
         None
     }
 
+    /// Attaches this context to a sender and labels the channel after it. See [Sender::annotate_with_context].
+    fn annotate_sender<T: DAMType>(&self, sender: &Sender<T>)
+    where
+        Self: Sized,
+    {
+        sender.annotate_with_context(self);
+    }
+
+    /// Attaches this context to a receiver and labels the channel after it. See [Receiver::annotate_with_context].
+    fn annotate_receiver<T: DAMType>(&self, receiver: &Receiver<T>)
+    where
+        Self: Sized,
+    {
+        receiver.annotate_with_context(self);
+    }
+
     /// Returns a summary of the context, which is then dropped by the programgraph.
     fn summarize(&self) -> ContextSummary {
         ContextSummary {
@@ -27,4 +27,13 @@ impl ContextSummary {
             .max()
             .unwrap()
     }
+
+    /// Gets the total number of ticks advanced by all leaf contexts under this summary.
+    pub fn total_time(&self) -> u64 {
+        if self.children.is_empty() {
+            self.time.tick_lower_bound().time()
+        } else {
+            self.children.iter().map(|child| child.total_time()).sum()
+        }
+    }
 }
@@ -43,6 +43,53 @@ impl Time {
         self.time
     }
 
+    /// Rounds down to the largest multiple of `period` which is no greater than this timestamp.
+    #[inline]
+    pub const fn floor_to(self, period: u64) -> Self {
+        assert!(period > 0);
+        Self {
+            time: self.time - self.time % period,
+            done: self.done,
+        }
+    }
+
+    /// Rounds up to the smallest multiple of `period` which is no less than this timestamp.
+    #[inline]
+    pub const fn ceil_to(self, period: u64) -> Self {
+        assert!(period > 0);
+        let remainder = self.time % period;
+        Self {
+            time: if remainder == 0 {
+                self.time
+            } else {
+                self.time + (period - remainder)
+            },
+            done: self.done,
+        }
+    }
+
+    /// Rounds up to the next timestamp of the form `k * period + phase`, for clock domains which are offset from zero.
+    /// The phase must be less than the period.
+    #[inline]
+    pub const fn align_to(self, period: u64, phase: u64) -> Self {
+        assert!(phase < period);
+        if self.time <= phase {
+            return Self {
+                time: phase,
+                done: self.done,
+            };
+        }
+        let shifted = Self {
+            time: self.time - phase,
+            done: self.done,
+        }
+        .ceil_to(period);
+        Self {
+            time: shifted.time + phase,
+            done: shifted.done,
+        }
+    }
+
     fn cmp(&self, other: &Self) -> Ordering {
         if self == other {
             Ordering::Equal
@@ -88,6 +135,16 @@ impl PartialEq for Time {
 
 impl Eq for Time {}
 
+impl std::hash::Hash for Time {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // All infinite times compare equal, regardless of the preserved timestamp.
+        self.done.hash(state);
+        if !self.done {
+            self.time.hash(state);
+        }
+    }
+}
+
 impl PartialOrd for Time {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(std::cmp::Ord::cmp(self, other))
@@ -292,4 +349,20 @@ mod tests {
         fin1 += 1;
         assert_eq!(fin1.time, 2);
     }
+
+    #[test]
+    fn time_quantize() {
+        let fin17 = Time::new(17);
+        assert_eq!(fin17.floor_to(4), Time::new(16));
+        assert_eq!(fin17.ceil_to(4), Time::new(20));
+
+        let fin16 = Time::new(16);
+        assert_eq!(fin16.floor_to(4), fin16);
+        assert_eq!(fin16.ceil_to(4), fin16);
+
+        assert_eq!(fin17.align_to(4, 1), Time::new(17));
+        assert_eq!(fin17.align_to(4, 3), Time::new(19));
+        assert_eq!(Time::new(0).align_to(4, 2), Time::new(2));
+        assert!(Time::infinite().ceil_to(4).is_infinite());
+    }
 }
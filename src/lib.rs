@@ -14,6 +14,7 @@ pub mod logging;
 pub mod shim;
 
 pub mod simulation;
+pub mod testing;
 pub mod types;
 pub mod utility_contexts;
 mod view;
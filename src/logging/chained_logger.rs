@@ -0,0 +1,146 @@
+use super::{LogEntry, LogProcessor};
+
+/// Runs several [LogProcessor]s side by side, each in its own thread, so that log entries can go to multiple destinations.
+/// The chain owns a [LogSplitter] reading from the log queue, and every processor is built on its own output queue, so each one sees every entry.
+pub struct ChainedLogProcessor {
+    splitter: LogSplitter,
+    processors: Vec<Box<dyn LogProcessor>>,
+}
+
+impl ChainedLogProcessor {
+    /// Constructs an empty chain reading from `queue`.
+    pub fn new(queue: crossbeam::channel::Receiver<LogEntry>) -> Self {
+        Self {
+            splitter: LogSplitter::new(queue),
+            processors: vec![],
+        }
+    }
+
+    /// Adds a processor to the chain, built by `make` from a queue which receives a copy of every entry.
+    /// For example, `ChainedLogProcessor::new(queue).chain(|queue| SqliteLogger::new(path, queue))`.
+    pub fn chain<P: LogProcessor + 'static>(
+        mut self,
+        make: impl FnOnce(crossbeam::channel::Receiver<LogEntry>) -> P,
+    ) -> Self {
+        let queue = self.splitter.output();
+        self.processors.push(Box::new(make(queue)));
+        self
+    }
+}
+
+impl LogProcessor for ChainedLogProcessor {
+    fn spawn(&mut self) {
+        let (splitter, processors) = (&mut self.splitter, &mut self.processors);
+        std::thread::scope(|s| {
+            s.spawn(|| splitter.spawn());
+            for processor in processors.iter_mut() {
+                s.spawn(move || processor.spawn());
+            }
+        });
+    }
+}
+
+/// Copies every entry from a single queue into any number of output queues, so that several processors can consume the same log.
+pub struct LogSplitter {
+    queue: crossbeam::channel::Receiver<LogEntry>,
+    outputs: Vec<crossbeam::channel::Sender<LogEntry>>,
+}
+
+impl LogSplitter {
+    /// Constructs a splitter reading from `queue`, with no outputs.
+    pub fn new(queue: crossbeam::channel::Receiver<LogEntry>) -> Self {
+        Self {
+            queue,
+            outputs: vec![],
+        }
+    }
+
+    /// Creates a new output queue, which will receive a copy of every entry.
+    pub fn output(&mut self) -> crossbeam::channel::Receiver<LogEntry> {
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        self.outputs.push(sender);
+        receiver
+    }
+}
+
+impl LogProcessor for LogSplitter {
+    fn spawn(&mut self) {
+        while let Ok(entry) = self.queue.recv() {
+            for output in &self.outputs {
+                // A processor which has stopped early shouldn't prevent the others from receiving entries.
+                let _ = output.send(entry.clone());
+            }
+        }
+        // Closes the output queues, so that downstream processors can finish.
+        self.outputs.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::ChainedLogProcessor;
+    use crate::{
+        channel::{events::ReceiverEvent, ChannelID},
+        datastructures::Time,
+        logging::{LogEntry, LogEvent, LogProcessor},
+    };
+
+    struct CollectingLogger {
+        queue: crossbeam::channel::Receiver<LogEntry>,
+        entries: Arc<Mutex<Vec<LogEntry>>>,
+    }
+
+    impl LogProcessor for CollectingLogger {
+        fn spawn(&mut self) {
+            while let Ok(entry) = self.queue.recv() {
+                self.entries.lock().unwrap().push(entry);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chained_loggers() {
+        const NUM_ENTRIES: u64 = 1000;
+        const NUM_LOGGERS: usize = 3;
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let collected: Vec<_> = (0..NUM_LOGGERS)
+            .map(|_| Arc::new(Mutex::new(vec![])))
+            .collect();
+        let mut chain =
+            collected
+                .iter()
+                .fold(ChainedLogProcessor::new(receiver), |chain, entries| {
+                    chain.chain(|queue| CollectingLogger {
+                        queue,
+                        entries: entries.clone(),
+                    })
+                });
+
+        let channel = ChannelID::new();
+        for tick in 0..NUM_ENTRIES {
+            sender
+                .send(LogEntry {
+                    timestamp: tick as i64,
+                    context: 0,
+                    ticks: Time::new(tick),
+                    event_type: ReceiverEvent::NAME.to_string(),
+                    event_data: bson::to_bson(&ReceiverEvent::Peek(channel)).unwrap(),
+                })
+                .unwrap();
+        }
+        drop(sender);
+        chain.spawn();
+
+        // Every logger sees every entry, in order.
+        let expected = bson::to_bson(&ReceiverEvent::Peek(channel)).unwrap();
+        for entries in collected {
+            let entries = entries.lock().unwrap();
+            assert_eq!(entries.len() as u64, NUM_ENTRIES);
+            assert!(entries.iter().zip(0..).all(
+                |(entry, tick)| entry.ticks == Time::new(tick) && entry.event_data == expected
+            ));
+        }
+    }
+}
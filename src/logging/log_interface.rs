@@ -21,20 +21,25 @@ impl LogInterface {
     /// May return an error if either the channel was prematurely closed, or if some aspect of serialization failed.
     #[allow(dead_code, unused)]
     pub fn log<T: LogEvent>(&self, event: &T) -> Result<(), LogError> {
-        self.comm
-            .send(LogEntry {
-                timestamp: self
-                    .base_time
-                    .elapsed()
-                    .as_micros()
-                    .try_into()
-                    .map_err(LogError::TimeConversionError)?,
-                context: self.id.id,
-                ticks: self.current_ticks,
-                event_type: T::NAME.to_string(),
-                event_data: bson::to_bson(event).map_err(LogError::SerializationError)?,
-            })
-            .map_err(|_| LogError::SendError)?;
+        if !self.log_filter.admits_tick(self.current_ticks) {
+            return Ok(());
+        }
+        let entry = LogEntry {
+            timestamp: self
+                .base_time
+                .elapsed()
+                .as_micros()
+                .try_into()
+                .map_err(LogError::TimeConversionError)?,
+            context: self.id.id,
+            ticks: self.current_ticks,
+            event_type: T::NAME.to_string(),
+            event_data: bson::to_bson(event).map_err(LogError::SerializationError)?,
+        };
+        if !self.log_filter.matches(&entry) {
+            return Ok(());
+        }
+        self.comm.send(entry).map_err(|_| LogError::SendError)?;
 
         Ok(())
     }
@@ -45,3 +50,99 @@ impl LogInterface {
         self.current_ticks = new_time;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::LogInterface;
+    use crate::{
+        channel::{events::ReceiverEvent, ChannelID},
+        datastructures::{Identifier, Time},
+        logging::{EntryFilter, LogEvent, LogFilter},
+    };
+
+    #[test]
+    fn test_channel_filter() {
+        let (kept, dropped) = (ChannelID::new(), ChannelID::new());
+        let filter = LogFilter::Entries(EntryFilter {
+            channel_ids: Some([kept].into()),
+            ..Default::default()
+        });
+        let (snd, rcv) = crossbeam::channel::unbounded();
+        let mut interface = LogInterface::new(
+            Identifier::new(),
+            snd,
+            std::time::Instant::now(),
+            filter,
+            Time::new(0),
+        );
+
+        for tick in 0..10 {
+            interface.update_ticks(Time::new(tick));
+            interface.log(&ReceiverEvent::Peek(kept)).unwrap();
+            interface.log(&ReceiverEvent::Peek(dropped)).unwrap();
+        }
+        drop(interface);
+
+        let entries: Vec<_> = rcv.into_iter().collect();
+        assert_eq!(entries.len(), 10);
+        let expected = bson::to_bson(&ReceiverEvent::Peek(kept)).unwrap();
+        assert!(entries.iter().all(|entry| entry.event_data == expected));
+    }
+
+    #[test]
+    fn test_tick_filter() {
+        let filter = LogFilter::Entries(EntryFilter {
+            min_tick: Some(Time::new(3)),
+            max_tick: Some(Time::new(5)),
+            ..Default::default()
+        });
+        let (snd, rcv) = crossbeam::channel::unbounded();
+        let mut interface = LogInterface::new(
+            Identifier::new(),
+            snd,
+            std::time::Instant::now(),
+            filter,
+            Time::new(0),
+        );
+
+        let channel = ChannelID::new();
+        for tick in 0..10 {
+            interface.update_ticks(Time::new(tick));
+            interface.log(&ReceiverEvent::Peek(channel)).unwrap();
+        }
+        drop(interface);
+
+        let ticks: Vec<_> = rcv.into_iter().map(|entry| entry.ticks.time()).collect();
+        assert_eq!(ticks, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_tick_filter_skips_serialization() {
+        // Fails to serialize, so it can only be logged successfully if it is filtered out first.
+        struct Unserializable;
+        impl serde::Serialize for Unserializable {
+            fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("Unserializable"))
+            }
+        }
+        impl LogEvent for Unserializable {
+            const NAME: &'static str = "Unserializable";
+        }
+
+        let filter = LogFilter::Entries(EntryFilter {
+            min_tick: Some(Time::new(3)),
+            ..Default::default()
+        });
+        let (snd, _rcv) = crossbeam::channel::unbounded();
+        let mut interface = LogInterface::new(
+            Identifier::new(),
+            snd,
+            std::time::Instant::now(),
+            filter,
+            Time::new(0),
+        );
+        assert!(interface.log(&Unserializable).is_ok());
+        interface.update_ticks(Time::new(3));
+        assert!(interface.log(&Unserializable).is_err());
+    }
+}
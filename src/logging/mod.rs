@@ -1,5 +1,5 @@
 //! Logging support for DAM execution
-//! Right now, the only logger we support is MongoDB, but support for SQL-type databases may be added in the future.
+//! Logs may be written to MongoDB (feature `log-mongo`), or to a local SQLite database (feature `log-sqlite`) when running a database server is not an option.
 //! It is important to note that DAM simulations can put out hundreds of GiB to TiB of logs in a single run, so any logger must be designed for scale.
 
 use bson::Bson;
@@ -11,17 +11,26 @@ use thiserror::Error;
 mod null_logger;
 pub use null_logger::*;
 
+mod chained_logger;
+pub use chained_logger::{ChainedLogProcessor, LogSplitter};
+
 // #[cfg_attr(docsrs, doc(cfg(feature = "log-mongo")))]
 #[cfg(feature = "log-mongo")]
 pub mod mongo_logger;
 
+#[cfg(feature = "log-sqlite")]
+pub mod sqlite_logger;
+
 mod log_interface;
 pub use log_interface::LogInterface;
 
 mod log_functions;
 pub use log_functions::*;
 
-use crate::datastructures::Time;
+use crate::{
+    channel::{events::channel_of, ChannelID},
+    datastructures::Time,
+};
 
 use self::registry::{get_metrics_vec, METRICS};
 
@@ -91,14 +100,68 @@ pub enum LogFilter {
 
     /// Only enable a subset of logs, based on their registered LogEvent::NAME
     Some(HashSet<String>),
+
+    /// Only enable logs which match an [EntryFilter], for finer-grained control over high-volume channel events
+    Entries(EntryFilter),
+}
+
+/// Filters individual log entries by channel, event type, and tick.
+/// Each criterion which is left as `None` admits everything.
+#[derive(Debug, Default, Clone)]
+pub struct EntryFilter {
+    /// Channels whose events should be kept. Events which aren't associated with a channel are unaffected.
+    pub channel_ids: Option<HashSet<ChannelID>>,
+
+    /// Event types to keep, based on their registered LogEvent::NAME
+    pub event_types: Option<HashSet<String>>,
+
+    /// The earliest tick (inclusive) to keep
+    pub min_tick: Option<Time>,
+
+    /// The latest tick (inclusive) to keep
+    pub max_tick: Option<Time>,
+}
+
+impl EntryFilter {
+    /// Checks whether an event type may pass the filter, without requiring an entry.
+    fn enabled(&self, name: &str) -> bool {
+        self.event_types
+            .as_ref()
+            .is_none_or(|types| types.contains(name))
+    }
+
+    /// Checks whether an entry at the given tick may pass the filter, without requiring an entry.
+    fn admits_tick(&self, ticks: Time) -> bool {
+        self.min_tick.is_none_or(|min| ticks >= min) && self.max_tick.is_none_or(|max| ticks <= max)
+    }
+
+    /// Checks whether an entry passes the filter.
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        self.enabled(&entry.event_type)
+            && self.admits_tick(entry.ticks)
+            && match &self.channel_ids {
+                // Finding the channel deserializes the event, so it is only done when filtering by channel.
+                Some(channels) => {
+                    channel_of(entry).is_none_or(|channel| channels.contains(&channel))
+                }
+                None => true,
+            }
+    }
 }
 
 impl LogFilter {
     /// Checks to see if all elements of the LogFilter are actually registered metrics.
     pub fn check(&self) -> Result<(), LogError> {
         match self {
-            LogFilter::AllowAll => Ok(()),
-            LogFilter::Some(set) => {
+            LogFilter::AllowAll
+            | LogFilter::Entries(EntryFilter {
+                event_types: None, ..
+            }) => Ok(()),
+            LogFilter::Some(set)
+            | LogFilter::Entries(EntryFilter {
+                event_types: Some(set),
+                ..
+            }) => {
                 let invalids: Vec<_> = set
                     .clone()
                     .into_iter()
@@ -120,6 +183,24 @@ impl LogFilter {
             LogFilter::AllowAll => true,
             LogFilter::Some(filter) if filter.contains(T::NAME) => true,
             LogFilter::Some(_) => false,
+            LogFilter::Entries(filter) => filter.enabled(T::NAME),
+        }
+    }
+
+    /// Checks whether an entry at the given tick may be logged, so that entries outside the tick range are never serialized.
+    pub(crate) fn admits_tick(&self, ticks: Time) -> bool {
+        match self {
+            LogFilter::Entries(filter) => filter.admits_tick(ticks),
+            LogFilter::AllowAll | LogFilter::Some(_) => true,
+        }
+    }
+
+    /// Checks whether a fully constructed entry should be logged.
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        match self {
+            LogFilter::AllowAll => true,
+            LogFilter::Some(filter) => filter.contains(&entry.event_type),
+            LogFilter::Entries(filter) => filter.matches(entry),
         }
     }
 }
@@ -0,0 +1,181 @@
+//! This module provides SQLite support for logging, for when running a MongoDB server isn't an option.
+//!
+//! The SqliteLogger takes in a [crossbeam::channel::Receiver] containing [LogEntry] and writes them to a local database file in batches.
+//! Each row keeps the full entry as a BSON payload, alongside indexed columns for the channel, event type, and tick.
+//! Logs can be queried after the run with a [SqliteLogReader].
+
+use std::path::{Path, PathBuf};
+
+use derive_more::Constructor;
+use rusqlite::{params, Connection};
+
+use crate::{
+    channel::{events::channel_of, ChannelID},
+    datastructures::Time,
+};
+
+use super::LogEntry;
+
+pub use rusqlite;
+
+const BATCH_SIZE: usize = 500;
+
+/// A logger using SQLite as the backing datastore.
+#[derive(Clone, Constructor)]
+pub struct SqliteLogger {
+    path: PathBuf,
+    queue: crossbeam::channel::Receiver<LogEntry>,
+}
+
+impl SqliteLogger {
+    fn write_batch(connection: &mut Connection, batch: &[LogEntry]) -> rusqlite::Result<()> {
+        let transaction = connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(
+                "INSERT INTO log_events (channel_id, event_type, tick, payload) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for entry in batch {
+                let payload = bson::to_vec(entry).expect("Failed to serialize log entry");
+                statement.execute(params![
+                    channel_of(entry).map(|id| id.as_usize() as i64),
+                    entry.event_type,
+                    entry.ticks.time() as i64,
+                    payload
+                ])?;
+            }
+        }
+        transaction.commit()
+    }
+}
+
+impl super::LogProcessor for SqliteLogger {
+    fn spawn(&mut self) {
+        let mut connection =
+            Connection::open(&self.path).expect("Error opening the SQLite database");
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS log_events (
+                    id INTEGER PRIMARY KEY,
+                    channel_id INTEGER,
+                    event_type TEXT NOT NULL,
+                    tick INTEGER NOT NULL,
+                    payload BLOB NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS log_events_tick ON log_events (tick);
+                CREATE INDEX IF NOT EXISTS log_events_channel ON log_events (channel_id);",
+            )
+            .expect("Error creating the log_events table");
+
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        // Block on the first entry of each batch, and then take whatever else is immediately available.
+        while let Ok(entry) = self.queue.recv() {
+            batch.push(entry);
+            while batch.len() < BATCH_SIZE {
+                match self.queue.try_recv() {
+                    Ok(entry) => batch.push(entry),
+                    Err(_) => break,
+                }
+            }
+            Self::write_batch(&mut connection, &batch).expect("Error writing log entries");
+            batch.clear();
+        }
+    }
+}
+
+/// Reads back the logs written by a [SqliteLogger].
+pub struct SqliteLogReader {
+    connection: Connection,
+}
+
+impl SqliteLogReader {
+    /// Opens an existing log database.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            connection: Connection::open(path)?,
+        })
+    }
+
+    /// Fetches all entries whose tick lies in the inclusive range [lo, hi], in insertion order.
+    pub fn query_by_tick_range(&self, lo: Time, hi: Time) -> rusqlite::Result<Vec<LogEntry>> {
+        self.query(
+            "SELECT payload FROM log_events WHERE tick BETWEEN ?1 AND ?2 ORDER BY id",
+            params![lo.time() as i64, hi.time() as i64],
+        )
+    }
+
+    /// Fetches all channel events on a given channel, in insertion order.
+    pub fn query_by_channel(&self, id: ChannelID) -> rusqlite::Result<Vec<LogEntry>> {
+        self.query(
+            "SELECT payload FROM log_events WHERE channel_id = ?1 ORDER BY id",
+            params![id.as_usize() as i64],
+        )
+    }
+
+    fn query(&self, sql: &str, params: impl rusqlite::Params) -> rusqlite::Result<Vec<LogEntry>> {
+        let mut statement = self.connection.prepare(sql)?;
+        let rows = statement.query_map(params, |row| {
+            let payload: Vec<u8> = row.get(0)?;
+            bson::from_slice(&payload).map_err(|err| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    0,
+                    rusqlite::types::Type::Blob,
+                    Box::new(err),
+                )
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SqliteLogReader, SqliteLogger};
+    use crate::{
+        channel::{events::ReceiverEvent, ChannelID},
+        datastructures::Time,
+        logging::{LogEntry, LogEvent, LogProcessor},
+    };
+
+    #[test]
+    fn test_sqlite_round_trip() {
+        const NUM_ENTRIES: u64 = 100000;
+        let path = std::env::temp_dir().join(format!(
+            "dam_sqlite_logger_{}_{}.db",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+
+        let channels = [ChannelID::new(), ChannelID::new()];
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        for tick in 0..NUM_ENTRIES {
+            let event = ReceiverEvent::DequeueStart(channels[(tick % 2) as usize]);
+            sender
+                .send(LogEntry {
+                    timestamp: tick as i64,
+                    context: 0,
+                    ticks: Time::new(tick),
+                    event_type: ReceiverEvent::NAME.to_string(),
+                    event_data: bson::to_bson(&event).unwrap(),
+                })
+                .unwrap();
+        }
+        drop(sender);
+        SqliteLogger::new(path.clone(), receiver).spawn();
+
+        let reader = SqliteLogReader::open(&path).unwrap();
+        let range = reader
+            .query_by_tick_range(Time::new(100), Time::new(199))
+            .unwrap();
+        assert_eq!(range.len(), 100);
+        assert!(range
+            .iter()
+            .zip(100..)
+            .all(|(entry, tick)| entry.ticks == Time::new(tick) && entry.timestamp == tick as i64));
+
+        let on_channel = reader.query_by_channel(channels[1]).unwrap();
+        assert_eq!(on_channel.len() as u64, NUM_ENTRIES / 2);
+        assert!(on_channel.iter().all(|entry| entry.ticks.time() % 2 == 1));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
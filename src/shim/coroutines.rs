@@ -17,6 +17,7 @@ pub use may::coroutine::scope;
 pub use may::coroutine::Builder;
 
 pub use may::coroutine::park;
+pub use may::coroutine::park_timeout;
 pub use may::coroutine::sleep;
 pub use may::coroutine::yield_now;
 pub use may::coroutine::Coroutine as Thread;
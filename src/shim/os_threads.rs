@@ -5,6 +5,7 @@ pub mod channel {
 
 pub use std::thread::current;
 pub use std::thread::park;
+pub use std::thread::park_timeout;
 pub use std::thread::scope;
 pub use std::thread::sleep;
 pub use std::thread::yield_now;
@@ -7,13 +7,18 @@ use crate::{
     channel::{
         channel_spec::ChannelSpec,
         handle::{ChannelData, ChannelHandle},
-        ChannelID, Receiver, Sender,
+        ChannelElement, ChannelID, Receiver, Sender,
     },
     context::Context,
-    datastructures::Identifier,
+    datastructures::{Identifiable, Identifier},
+    types::DAMType,
+    view::{TimeView, TimeViewable},
 };
 
-use super::{programdata::ProgramData, InitializationError, InitializationOptions, Initialized};
+use super::{
+    programdata::ProgramData, InitializationError, InitializationOptions, Initialized,
+    PortDirection,
+};
 
 #[derive(Copy, Clone, Eq, Debug, PartialEq, Hash)]
 enum ChannelOrContext {
@@ -48,7 +53,14 @@ impl<'a> ProgramBuilder<'a> {
     where
         T: Clone + 'a,
     {
-        let spec = Arc::new(ChannelSpec::new(capacity, latency, resp_latency));
+        self.make_channel_from_spec(ChannelSpec::new(capacity, latency, resp_latency))
+    }
+
+    fn make_channel_from_spec<T>(&mut self, spec: ChannelSpec) -> (Sender<T>, Receiver<T>)
+    where
+        T: Clone + 'a,
+    {
+        let spec = Arc::new(spec);
         let underlying = Arc::new(ChannelData::new(spec));
         self.add_edge(underlying.clone());
 
@@ -75,6 +87,22 @@ impl<'a> ProgramBuilder<'a> {
         self.make_channel_with_latency(Some(capacity), Some(latency), Some(resp_latency))
     }
 
+    /// Constructs a bounded channel with unit latency, whose elements are stored compressed.
+    /// See [crate::channel::Compressed].
+    #[cfg(feature = "compress")]
+    pub fn bounded_compressed<T>(
+        &mut self,
+        capacity: usize,
+    ) -> (
+        Sender<crate::channel::Compressed<T>>,
+        Receiver<crate::channel::Compressed<T>>,
+    )
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + 'a,
+    {
+        self.bounded(capacity)
+    }
+
     /// Constructs an infinitely deep channel with unit latency
     pub fn unbounded<T: Clone + 'a>(&mut self) -> (Sender<T>, Receiver<T>) {
         self.make_channel_with_latency(None, None, None)
@@ -91,7 +119,11 @@ impl<'a> ProgramBuilder<'a> {
 
     /// Constructs a channel which writes to nowhere
     pub fn void<T: Clone + 'a>(&mut self) -> Sender<T> {
-        let spec = Arc::new(ChannelSpec::new(None, None, None));
+        self.make_void_from_spec(ChannelSpec::new(None, None, None))
+    }
+
+    fn make_void_from_spec<T: Clone + 'a>(&mut self, spec: ChannelSpec) -> Sender<T> {
+        let spec = Arc::new(spec);
         let underlying = Arc::new(ChannelData::new(spec));
         self.add_void_edge(underlying.clone());
         Sender { underlying }
@@ -106,11 +138,114 @@ impl<'a> ProgramBuilder<'a> {
         self.add_node(Box::new(child));
     }
 
+    /// Pre-populates a channel with tokens, which are available to the receiver as soon as the program starts.
+    /// This is the standard way of initializing feedback channels in cyclic programs.
+    /// The tokens bypass the usual time checks, since there is no running sender context yet, but they do occupy capacity.
+    pub fn inject_initial_tokens<T: DAMType>(
+        &mut self,
+        channel: &Sender<T>,
+        tokens: Vec<ChannelElement<T>>,
+    ) -> Result<(), InitializationError> {
+        let num_tokens = tokens.len();
+        channel
+            .underlying
+            .push_initial_tokens(tokens)
+            .map_err(|capacity| InitializationError::TooManyInitialTokens {
+                channel: channel.id(),
+                tokens: num_tokens,
+                capacity,
+            })
+    }
+
+    /// Returns a view of the builder whose channels are tagged with the given namespace.
+    /// Channels created outside of the namespace are unaffected.
+    pub fn with_namespace<'b>(&'b mut self, namespace: &str) -> NamespacedProgramBuilder<'b, 'a> {
+        NamespacedProgramBuilder {
+            builder: self,
+            namespace: namespace.into(),
+        }
+    }
+
+    /// Bounds a top-level context to `max_ticks` ticks. Once the context runs past its budget, its channel operations fail as if the channels were closed,
+    /// so that it can wind down and its neighbors observe the closure.
+    /// Only contexts with their own time (i.e. not parent contexts) can be bounded; otherwise this returns [InitializationError::UnregisteredNode].
+    pub fn with_context_timeout(
+        &mut self,
+        id: Identifier,
+        max_ticks: u64,
+    ) -> Result<(), InitializationError> {
+        let view = self
+            .data
+            .nodes
+            .iter()
+            .find(|node| node.id() == id)
+            .map(|node| node.view());
+        match view {
+            Some(TimeView::BasicContextView(view)) => {
+                view.set_tick_budget(max_ticks);
+                Ok(())
+            }
+            _ => Err(InitializationError::UnregisteredNode(id)),
+        }
+    }
+
+    /// Declares how many channels context `id` should receive from, which is then checked by [ProgramBuilder::validate] and [ProgramBuilder::initialize].
+    /// This is an opt-in aid for catching unconnected ports; declaring 0 inputs (e.g. for variadic contexts) disables the check.
+    pub fn declare_inputs(&mut self, id: Identifier, count: usize) {
+        self.declare_ports(id, PortDirection::Input, count);
+    }
+
+    /// Declares how many channels context `id` should send to, see [ProgramBuilder::declare_inputs].
+    pub fn declare_outputs(&mut self, id: Identifier, count: usize) {
+        self.declare_ports(id, PortDirection::Output, count);
+    }
+
+    fn declare_ports(&mut self, id: Identifier, direction: PortDirection, count: usize) {
+        if count == 0 {
+            self.data.declared_ports.remove(&(id, direction));
+        } else {
+            self.data.declared_ports.insert((id, direction), count);
+        }
+    }
+
     /// Returns how many children there are in the constructed graph
     pub fn num_children(&self) -> usize {
         self.data.nodes.len()
     }
 
+    /// Lists the IDs of the top-level contexts, in the order they were added.
+    pub fn context_ids(&self) -> Vec<Identifier> {
+        self.data.node_ids()
+    }
+
+    /// Looks up a top-level context by its ID. Children of parent contexts are not included.
+    pub fn context_by_id(&self, id: Identifier) -> Option<&(dyn Context + 'a)> {
+        self.data.node_by_id(id)
+    }
+
+    /// Lists the channels connecting contexts, as (sender, receiver, channel) triples.
+    /// Channels which are missing an endpoint are omitted; see [ProgramBuilder::validate] for those.
+    pub fn connected_contexts(&self) -> Vec<(Identifier, Identifier, ChannelID)> {
+        self.data.connections()
+    }
+
+    /// Lists the top-level contexts which are not attached to any channel, either directly or through their children.
+    /// These are legal, but are frequently a sign of a wiring mistake.
+    pub fn isolated_contexts(&self) -> Vec<Identifier> {
+        self.data.isolated_nodes()
+    }
+
+    /// Checks the program for configuration errors without initializing it.
+    /// Unlike [ProgramBuilder::initialize], this reports every issue that was found rather than only the first.
+    pub fn validate(&self) -> Result<(), Vec<InitializationError>> {
+        let issues = self.data.validate();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
     /// Initializes the program, and returns an [Initialized] program if successful.
     /// On error, returns a [InitializationError], which encodes the first error that occurred.
     pub fn initialize(
@@ -118,6 +253,11 @@ impl<'a> ProgramBuilder<'a> {
         options: InitializationOptions,
     ) -> Result<Initialized<'a>, InitializationError> {
         self.data.check()?;
+        self.data
+            .edges
+            .iter()
+            .chain(self.data.void_edges.iter())
+            .for_each(|edge| edge.spec().set_timeout_policy(options.timeout_policy));
         self.data
             .void_edges
             .iter()
@@ -236,3 +376,219 @@ impl<'a> ProgramBuilder<'a> {
         Ok(Initialized { data: self.data })
     }
 }
+
+/// A [ProgramBuilder] which tags every channel it constructs with a namespace, see [crate::channel::NamespacedChannelID].
+pub struct NamespacedProgramBuilder<'b, 'a> {
+    builder: &'b mut ProgramBuilder<'a>,
+    namespace: Arc<str>,
+}
+
+impl<'a> NamespacedProgramBuilder<'_, 'a> {
+    fn make_channel_with_latency<T>(
+        &mut self,
+        capacity: Option<usize>,
+        latency: Option<u64>,
+        resp_latency: Option<u64>,
+    ) -> (Sender<T>, Receiver<T>)
+    where
+        T: Clone + 'a,
+    {
+        self.builder.make_channel_from_spec(
+            ChannelSpec::new(capacity, latency, resp_latency)
+                .with_namespace(self.namespace.clone()),
+        )
+    }
+
+    /// The namespace of the builder.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// See: [ProgramBuilder::bounded]
+    pub fn bounded<T: Clone + 'a>(&mut self, capacity: usize) -> (Sender<T>, Receiver<T>) {
+        self.make_channel_with_latency(Some(capacity), None, None)
+    }
+
+    /// See: [ProgramBuilder::bounded_with_latency]
+    pub fn bounded_with_latency<T: Clone + 'a>(
+        &mut self,
+        capacity: usize,
+        latency: u64,
+        resp_latency: u64,
+    ) -> (Sender<T>, Receiver<T>) {
+        self.make_channel_with_latency(Some(capacity), Some(latency), Some(resp_latency))
+    }
+
+    /// See: [ProgramBuilder::unbounded]
+    pub fn unbounded<T: Clone + 'a>(&mut self) -> (Sender<T>, Receiver<T>) {
+        self.make_channel_with_latency(None, None, None)
+    }
+
+    /// See: [ProgramBuilder::unbounded_with_latency]
+    pub fn unbounded_with_latency<T: Clone + 'a>(
+        &mut self,
+        latency: u64,
+        resp_latency: u64,
+    ) -> (Sender<T>, Receiver<T>) {
+        self.make_channel_with_latency(None, Some(latency), Some(resp_latency))
+    }
+
+    /// See: [ProgramBuilder::void]
+    pub fn void<T: Clone + 'a>(&mut self) -> Sender<T> {
+        self.builder.make_void_from_spec(
+            ChannelSpec::new(None, None, None).with_namespace(self.namespace.clone()),
+        )
+    }
+
+    /// See: [ProgramBuilder::add_child]
+    pub fn add_child<T>(&mut self, child: T)
+    where
+        T: Context + 'a,
+    {
+        self.builder.add_child(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        datastructures::Identifiable,
+        simulation::{InitializationError, PortDirection, ProgramBuilder},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_unconnected_port() {
+        let mut builder = ProgramBuilder::default();
+        let (snd, rcv) = builder.bounded::<u32>(4);
+        let (_unused_snd, unused_rcv) = builder.bounded::<u32>(4);
+
+        let producer = FunctionContext::default();
+        let consumer = FunctionContext::default();
+        snd.attach_sender(&producer);
+        rcv.attach_receiver(&consumer);
+        unused_rcv.attach_receiver(&consumer);
+        let (producer_id, consumer_id) = (producer.id(), consumer.id());
+        builder.add_child(producer);
+        builder.add_child(consumer);
+
+        // The producer is missing its second output.
+        builder.declare_outputs(producer_id, 2);
+        builder.declare_inputs(consumer_id, 2);
+        // Variadic contexts opt out of checking.
+        builder.declare_inputs(producer_id, 0);
+
+        let issues = builder.validate().unwrap_err();
+        let unconnected: Vec<_> = issues
+            .iter()
+            .filter_map(|issue| match issue {
+                InitializationError::UnconnectedPort {
+                    node,
+                    direction,
+                    declared,
+                    connected,
+                } => Some((*node, *direction, *declared, *connected)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            unconnected,
+            vec![(producer_id, PortDirection::Output, 2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_all_issues() {
+        let mut builder = ProgramBuilder::default();
+
+        let (disconnected_snd, disconnected_rcv) = builder.bounded::<u32>(4);
+        let (zero_snd, zero_rcv) = builder.bounded::<u32>(0);
+        let (unregistered_snd, unregistered_rcv) = builder.unbounded::<u32>();
+
+        let ctx = FunctionContext::default();
+        disconnected_rcv.attach_receiver(&ctx);
+        zero_snd.attach_sender(&ctx);
+        zero_rcv.attach_receiver(&ctx);
+        unregistered_snd.attach_sender(&ctx);
+
+        let orphan = FunctionContext::default();
+        unregistered_rcv.attach_receiver(&orphan);
+        let orphan_id = orphan.id();
+        builder.add_child(ctx);
+
+        let issues = builder.validate().unwrap_err();
+        assert_eq!(issues.len(), 3);
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            InitializationError::DisconnectedSender(id) if *id == disconnected_snd.id()
+        )));
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            InitializationError::ZeroCapacity(id) if *id == zero_snd.id()
+        )));
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            InitializationError::UnregisteredNode(id) if *id == orphan_id
+        )));
+    }
+
+    #[test]
+    fn test_topology() {
+        let mut builder = ProgramBuilder::default();
+        let (snd, rcv) = builder.bounded::<u32>(4);
+
+        let producer = FunctionContext::default();
+        let consumer = FunctionContext::default();
+        let isolated = FunctionContext::default();
+        snd.attach_sender(&producer);
+        rcv.attach_receiver(&consumer);
+        let (producer_id, consumer_id, isolated_id) = (producer.id(), consumer.id(), isolated.id());
+        builder.add_child(producer);
+        builder.add_child(consumer);
+        builder.add_child(isolated);
+
+        assert_eq!(
+            builder.connected_contexts(),
+            vec![(producer_id, consumer_id, snd.id())]
+        );
+        assert_eq!(builder.isolated_contexts(), vec![isolated_id]);
+    }
+
+    #[test]
+    fn test_context_lookup() {
+        let mut builder = ProgramBuilder::default();
+        let contexts: Vec<_> = (0..4).map(|_| FunctionContext::default()).collect();
+        let ids: Vec<_> = contexts.iter().map(|ctx| ctx.id()).collect();
+        contexts.into_iter().for_each(|ctx| builder.add_child(ctx));
+
+        assert_eq!(builder.context_ids(), ids);
+        for id in ids {
+            assert_eq!(builder.context_by_id(id).unwrap().id(), id);
+        }
+        let stray = FunctionContext::default();
+        assert!(builder.context_by_id(stray.id()).is_none());
+    }
+
+    #[test]
+    fn test_namespaced_channels() {
+        let mut builder = ProgramBuilder::default();
+        let (plain, _) = builder.bounded::<u32>(4);
+        let (cached, _) = builder.with_namespace("cache").bounded::<u32>(4);
+
+        assert!(plain.namespaced_id().is_none());
+        let namespaced = cached.namespaced_id().unwrap();
+        assert_eq!(namespaced.id, cached.id());
+        assert!(namespaced.to_string().starts_with("cache::chan-"));
+    }
+
+    #[test]
+    fn test_validate_ok() {
+        let mut builder = ProgramBuilder::default();
+        let (snd, rcv) = builder.bounded::<u32>(4);
+        let ctx = FunctionContext::default();
+        snd.attach_sender(&ctx);
+        rcv.attach_receiver(&ctx);
+        builder.add_child(ctx);
+        assert!(builder.validate().is_ok());
+    }
+}
@@ -1,6 +1,10 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use crate::{channel::handle::ChannelHandle, context::ContextSummary};
+use crate::{
+    channel::{handle::ChannelHandle, ChannelID},
+    context::ContextSummary,
+    datastructures::Identifier,
+};
 
 use super::SimulationError;
 
@@ -10,8 +14,6 @@ pub struct Executed<'a> {
     pub(super) nodes: Vec<ContextSummary>,
     pub(super) failures: Vec<SimulationError>,
 
-    // Edges might not be used if the dot cfg isn't enabled.
-    #[allow(unused)]
     pub(super) edges: Vec<Arc<dyn ChannelHandle + 'a>>,
 }
 
@@ -22,6 +24,210 @@ impl Executed<'_> {
         self.nodes.iter().map(|node| node.max_time()).max()
     }
 
+    /// Computes the average parallelism of the run: the ticks advanced by all contexts, divided by the elapsed cycles.
+    /// Values above 1 indicate that contexts were doing work concurrently, while values below 1 indicate serial bottlenecks.
+    /// Returns None if no time elapsed.
+    pub fn measure_parallelism(&self) -> Option<f64> {
+        let elapsed = self.elapsed_cycles().filter(|elapsed| *elapsed > 0)?;
+        let total: u64 = self.nodes.iter().map(|node| node.total_time()).sum();
+        Some(total as f64 / elapsed as f64)
+    }
+
+    /// Computes how much faster than real time the simulated hardware ran, given its clock frequency in ticks per second
+    /// and the wall-clock time of the run (see [super::Initialized::run_timed]).
+    /// Values above 1 mean the simulation ran faster than the hardware would have.
+    /// Returns None if no time elapsed, or if the wall-clock time was zero.
+    pub fn measure_speedup(
+        &self,
+        ticks_per_second: f64,
+        reference_wall_time: std::time::Duration,
+    ) -> Option<f64> {
+        let elapsed = self.elapsed_cycles().filter(|elapsed| *elapsed > 0)?;
+        let wall_seconds = Some(reference_wall_time.as_secs_f64()).filter(|wall| *wall > 0.0)?;
+        Some(elapsed as f64 / ticks_per_second / wall_seconds)
+    }
+
+    /// Gets the summaries of the top-level contexts which completed successfully, in no particular order.
+    pub fn summaries(&self) -> &[ContextSummary] {
+        &self.nodes
+    }
+
+    /// Writes the context hierarchy in the folded stack format consumed by flamegraph tools such as inferno or flamegraph.pl,
+    /// with each leaf context weighted by the number of ticks it advanced.
+    pub fn write_folded_stacks(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        let mut summaries: Vec<_> = self.nodes.iter().collect();
+        summaries.sort_by_key(|summary| summary.id.id.id);
+        summaries
+            .into_iter()
+            .try_for_each(|summary| Self::fold_summary(summary, "", &mut out))
+    }
+
+    fn fold_summary(
+        summary: &ContextSummary,
+        prefix: &str,
+        out: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        // Spaces and semicolons are delimiters in the folded format.
+        let frame = format!("{}({})", summary.id.name, summary.id.id).replace([' ', ';'], "_");
+        let stack = format!("{prefix}{frame}");
+        if summary.children.is_empty() {
+            writeln!(out, "{stack} {}", summary.total_time())
+        } else {
+            let prefix = format!("{stack};");
+            summary
+                .children
+                .iter()
+                .try_for_each(|child| Self::fold_summary(child, &prefix, out))
+        }
+    }
+
+    /// The total number of elements which were dequeued across every channel in the program.
+    pub fn total_message_count(&self) -> u64 {
+        self.edges
+            .iter()
+            .map(|edge| edge.spec().message_count())
+            .sum()
+    }
+
+    /// The number of elements which were dequeued from a single channel, or 0 if the channel is not part of the program.
+    pub fn message_count_for(&self, channel: ChannelID) -> u64 {
+        self.edges
+            .iter()
+            .find(|edge| edge.id() == channel)
+            .map_or(0, |edge| edge.spec().message_count())
+    }
+
+    /// Prints [Executed::write_topology_report] to stdout.
+    pub fn print_topology_report(&self) {
+        self.write_topology_report(std::io::stdout().lock())
+            .expect("Failed to write topology report to stdout");
+    }
+
+    /// Writes an aligned table with one row per channel, listing its endpoints, capacity, the number of elements dequeued,
+    /// and the fraction of the run its sender spent stalled on a full channel.
+    /// Channels which carried no elements are flagged as idle, and channels whose sender stalled on every element are flagged as always full.
+    pub fn write_topology_report(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        let mut names = HashMap::new();
+        self.nodes
+            .iter()
+            .for_each(|summary| Self::collect_names(summary, &mut names));
+        let endpoint = |id: Option<Identifier>| match id {
+            Some(id) => names.get(&id).cloned().unwrap_or_else(|| id.to_string()),
+            None => "-".to_string(),
+        };
+        let elapsed = self.elapsed_cycles().unwrap_or(0);
+
+        let mut edges: Vec<_> = self.edges.iter().collect();
+        edges.sort_by_key(|edge| edge.id().as_usize());
+        let header = [
+            "channel",
+            "sender",
+            "receiver",
+            "capacity",
+            "messages",
+            "stall_fraction",
+            "notes",
+        ]
+        .map(String::from);
+        let rows: Vec<[String; 7]> = edges
+            .into_iter()
+            .map(|edge| {
+                let spec = edge.spec();
+                let messages = spec.message_count();
+                let stalls = spec.stall_histogram();
+                let stall_fraction = match elapsed {
+                    0 => 0.0,
+                    elapsed => stalls.total_stall_time() as f64 / elapsed as f64,
+                };
+                let notes = if messages == 0 {
+                    "idle"
+                } else if stalls.num_stalls() >= messages {
+                    "always full"
+                } else {
+                    ""
+                };
+                [
+                    spec.describe(),
+                    endpoint(spec.sender_id()),
+                    endpoint(spec.receiver_id()),
+                    spec.capacity()
+                        .map_or("unbounded".to_string(), |capacity| capacity.to_string()),
+                    messages.to_string(),
+                    format!("{stall_fraction:.2}"),
+                    notes.to_string(),
+                ]
+            })
+            .collect();
+
+        let mut widths = [0; 7];
+        for row in std::iter::once(&header).chain(&rows) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            writeln!(out, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+
+    fn collect_names(summary: &ContextSummary, names: &mut HashMap<Identifier, String>) {
+        names.insert(
+            summary.id.id,
+            format!("{}({})", summary.id.name, summary.id.id),
+        );
+        summary
+            .children
+            .iter()
+            .for_each(|child| Self::collect_names(child, names));
+    }
+
+    /// Panics unless the given context advanced by at least `min_ticks`, which catches contexts that silently exited early.
+    /// Children of parent contexts are searched as well.
+    pub fn assert_context_advanced(&self, id: Identifier, min_ticks: u64) {
+        let summary = self
+            .find_summary(id)
+            .unwrap_or_else(|| panic!("Context {id} did not complete successfully"));
+        let ticks = summary.max_time();
+        assert!(
+            ticks >= min_ticks,
+            "{}({id}) only advanced {ticks} ticks, expected at least {min_ticks}",
+            summary.id.name
+        );
+    }
+
+    /// Panics if the given context was ever stalled for more than `max_stall_ticks` consecutive ticks while sending to a full channel.
+    pub fn assert_no_context_stalled_for(&self, id: Identifier, max_stall_ticks: u64) {
+        for edge in &self.edges {
+            let spec = edge.spec();
+            if spec.sender_id() != Some(id) {
+                continue;
+            }
+            let longest = spec.stall_histogram().percentile(100.0);
+            assert!(
+                longest <= max_stall_ticks,
+                "{id} stalled for {longest} ticks sending on {}, expected at most {max_stall_ticks}",
+                spec.describe()
+            );
+        }
+    }
+
+    fn find_summary(&self, id: Identifier) -> Option<&ContextSummary> {
+        fn search(summary: &ContextSummary, id: Identifier) -> Option<&ContextSummary> {
+            if summary.id.id == id {
+                return Some(summary);
+            }
+            summary.children.iter().find_map(|child| search(child, id))
+        }
+        self.nodes.iter().find_map(|summary| search(summary, id))
+    }
+
     /// Returns if simulation was successful with no errors.
     pub fn passed(&self) -> bool {
         self.failures.is_empty()
@@ -104,3 +310,196 @@ cfg_if::cfg_if! {
 
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        channel::{ChannelElement, ChannelID},
+        datastructures::Identifiable,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::{CheckerContext, FunctionContext, GeneratorContext},
+    };
+
+    #[test]
+    fn test_measure_parallelism() {
+        let mut parent = ProgramBuilder::default();
+        for _ in 0..4 {
+            let mut ctx = FunctionContext::default();
+            ctx.set_run(|time| time.incr_cycles(10));
+            parent.add_child(ctx);
+        }
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert_eq!(executed.elapsed_cycles(), Some(10));
+        assert_eq!(executed.measure_parallelism(), Some(4.0));
+    }
+
+    #[test]
+    fn test_measure_speedup() {
+        let mut parent = ProgramBuilder::default();
+        let mut ctx = FunctionContext::default();
+        ctx.set_run(|time| time.incr_cycles(1000));
+        parent.add_child(ctx);
+        let (executed, wall_time) = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run_timed(RunOptions::default());
+        assert!(wall_time > Duration::ZERO);
+
+        // 1000 ticks at 100 ticks per second is 10 seconds of simulated time.
+        assert_eq!(
+            executed.measure_speedup(100.0, Duration::from_secs(5)),
+            Some(2.0)
+        );
+        assert_eq!(executed.measure_speedup(100.0, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_assert_context_advanced() {
+        let mut parent = ProgramBuilder::default();
+        let (snd, rcv) = parent.bounded(1);
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..8u32 {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+            }
+        });
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            while rcv.dequeue(time).is_ok() {
+                time.incr_cycles(4);
+            }
+        });
+        let (sender_id, receiver_id) = (sender.id(), receiver.id());
+        parent.add_child(sender);
+        parent.add_child(receiver);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+        executed.assert_context_advanced(receiver_id, 32);
+        executed.assert_no_context_stalled_for(sender_id, 8);
+        // The receiver never sends, so it can't have stalled.
+        executed.assert_no_context_stalled_for(receiver_id, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "only advanced 10 ticks, expected at least 11")]
+    fn test_assert_context_advanced_failure() {
+        let mut parent = ProgramBuilder::default();
+        let mut ctx = FunctionContext::default();
+        ctx.set_run(|time| time.incr_cycles(10));
+        let id = ctx.id();
+        parent.add_child(ctx);
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        executed.assert_context_advanced(id, 11);
+    }
+
+    #[test]
+    fn test_message_counts() {
+        let mut parent = ProgramBuilder::default();
+        let (short_snd, short_rcv) = parent.bounded(4);
+        let (long_snd, long_rcv) = parent.bounded(4);
+        let (short_id, long_id) = (short_snd.id(), long_snd.id());
+        parent.add_child(GeneratorContext::new(|| 0..10u32, short_snd));
+        parent.add_child(CheckerContext::new(|| 0..10u32, short_rcv));
+        parent.add_child(GeneratorContext::new(|| 0..25u32, long_snd));
+        parent.add_child(CheckerContext::new(|| 0..25u32, long_rcv));
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+        assert_eq!(executed.message_count_for(short_id), 10);
+        assert_eq!(executed.message_count_for(long_id), 25);
+        assert_eq!(executed.message_count_for(ChannelID::new()), 0);
+        assert_eq!(executed.total_message_count(), 35);
+    }
+
+    #[test]
+    fn test_topology_report() {
+        let mut parent = ProgramBuilder::default();
+        let (busy_snd, busy_rcv) = parent.bounded(2);
+        let (idle_snd, idle_rcv) = parent.unbounded::<u32>();
+        busy_snd.set_debug_label("busy");
+        idle_snd.set_debug_label("idle");
+        parent.add_child(GeneratorContext::new(|| 0..10u32, busy_snd));
+        parent.add_child(CheckerContext::new(|| 0..10u32, busy_rcv));
+        parent.add_child(GeneratorContext::new(|| 0..0u32, idle_snd));
+        parent.add_child(CheckerContext::new(|| 0..0u32, idle_rcv));
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+
+        let mut report = vec![];
+        executed.write_topology_report(&mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("channel"));
+        let row = |label: &str| -> Vec<String> {
+            lines
+                .iter()
+                .find(|line| line.contains(label))
+                .unwrap()
+                .split('|')
+                .map(|cell| cell.trim().to_string())
+                .collect()
+        };
+
+        let busy = row("(busy)");
+        assert!(busy[1].starts_with("GeneratorContext"));
+        assert!(busy[2].starts_with("CheckerContext"));
+        assert_eq!(busy[3], "2");
+        assert_eq!(busy[4], "10");
+
+        let idle = row("(idle)");
+        assert_eq!(idle[3], "unbounded");
+        assert_eq!(idle[4], "0");
+        assert_eq!(idle[6], "idle");
+    }
+
+    #[test]
+    fn test_folded_stacks() {
+        let mut parent = ProgramBuilder::default();
+        for cycles in 1..=5 {
+            let mut ctx = FunctionContext::default();
+            ctx.set_run(move |time| time.incr_cycles(cycles * 10));
+            parent.add_child(ctx);
+        }
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+
+        let mut folded = vec![];
+        executed.write_folded_stacks(&mut folded).unwrap();
+        let folded = String::from_utf8(folded).unwrap();
+        let weights: Vec<u64> = folded
+            .lines()
+            .map(|line| {
+                let (stack, weight) = line.rsplit_once(' ').unwrap();
+                assert!(stack.contains("FunctionContext"));
+                weight.parse().unwrap()
+            })
+            .collect();
+        // Contexts are listed in the order in which they were constructed.
+        assert_eq!(weights, vec![10, 20, 30, 40, 50]);
+    }
+}
@@ -1,12 +1,17 @@
 use crate::{
-    datastructures::Time,
+    context::Context,
+    datastructures::{Identifier, Time},
     logging::{initialize_log, LogEntry, LogInterface, LogProcessor},
     shim::spawn,
+    view::{ContextView, TimeViewable},
 };
 
 #[cfg(feature = "log-mongo")]
 use crate::logging::mongo_logger::{mongodb, MongoLogger};
 
+#[cfg(feature = "log-sqlite")]
+use crate::logging::sqlite_logger::SqliteLogger;
+
 use super::{executed::Executed, programdata::ProgramData, LoggingOptions, RunOptions};
 
 /// An initialized program, which has passed checking after the [super::ProgramBuilder]
@@ -15,6 +20,16 @@ pub struct Initialized<'a> {
 }
 
 impl<'a> Initialized<'a> {
+    /// Lists the IDs of the top-level contexts, in the order they were added.
+    pub fn context_ids(&self) -> Vec<Identifier> {
+        self.data.node_ids()
+    }
+
+    /// Looks up a top-level context by its ID. Children of parent contexts are not included.
+    pub fn context_by_id(&self, id: Identifier) -> Option<&(dyn Context + 'a)> {
+        self.data.node_by_id(id)
+    }
+
     /// Executes the program with specified options.
     /// Currently will deadlock frequently if there is an error at runtime, due to blocking dequeues.
     pub fn run(mut self, options: RunOptions) -> Executed<'a> {
@@ -107,6 +122,50 @@ impl<'a> Initialized<'a> {
         }
     }
 
+    /// Executes the program like [Initialized::run], additionally returning the wall-clock time taken.
+    /// This can be combined with [Executed::measure_speedup] to compare simulated time against real time.
+    pub fn run_timed(self, options: RunOptions) -> (Executed<'a>, std::time::Duration) {
+        let start = std::time::Instant::now();
+        let executed = self.run(options);
+        (executed, start.elapsed())
+    }
+
+    /// Executes the program like [Initialized::run], while a watcher thread reports progress every `interval` of wall-clock time.
+    /// Each report passes the furthest tick reached so far by any top-level context which is still running.
+    /// A report is only made when that tick has increased since the last one, so the reported ticks are strictly increasing.
+    pub fn run_with_progress(
+        self,
+        options: RunOptions,
+        interval: std::time::Duration,
+        callback: impl Fn(Time) + Send + 'static,
+    ) -> Executed<'a> {
+        let views: Vec<_> = self.data.nodes.iter().map(|node| node.view()).collect();
+        let (done_snd, done_rcv) = crossbeam::channel::bounded::<()>(0);
+        let watcher = std::thread::spawn(move || {
+            let mut reported: Option<Time> = None;
+            while let Err(crossbeam::channel::RecvTimeoutError::Timeout) =
+                done_rcv.recv_timeout(interval)
+            {
+                let furthest = views
+                    .iter()
+                    .map(|view| view.tick_lower_bound())
+                    .filter(|time| !time.is_infinite())
+                    .max();
+                if let Some(time) = furthest {
+                    if reported < Some(time) {
+                        reported = Some(time);
+                        callback(time);
+                    }
+                }
+            }
+        });
+
+        let executed = self.run(options);
+        drop(done_snd);
+        watcher.join().expect("Progress watcher panicked");
+        executed
+    }
+
     // The queue is sometimes unused when no logger is set.
     fn make_logger(
         #[allow(unused)] queue: crossbeam::channel::Receiver<LogEntry>,
@@ -124,6 +183,10 @@ impl<'a> Initialized<'a> {
                 mongo_opts.col_options,
                 queue,
             ))),
+            #[cfg(feature = "log-sqlite")]
+            super::LoggingOptions::Sqlite(sqlite_opts) => {
+                Some(Box::new(SqliteLogger::new(sqlite_opts.path, queue)))
+            }
         })
     }
 }
@@ -208,3 +271,46 @@ mod inner {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_run_with_progress() {
+        let mut parent = ProgramBuilder::default();
+        let mut ctx = FunctionContext::default();
+        ctx.set_run(|time| {
+            for _ in 0..20 {
+                time.incr_cycles(10);
+                crate::shim::sleep(Duration::from_millis(10));
+            }
+        });
+        parent.add_child(ctx);
+
+        let reports = Arc::new(Mutex::new(vec![]));
+        let reports_handle = reports.clone();
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run_with_progress(
+                RunOptions::default(),
+                Duration::from_millis(20),
+                move |time| reports_handle.lock().unwrap().push(time),
+            );
+        assert!(executed.passed());
+
+        let reports = reports.lock().unwrap();
+        assert!(reports.len() >= 3, "{reports:?}");
+        assert!(reports.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(reports.iter().all(|time| time.time() <= 200));
+    }
+}
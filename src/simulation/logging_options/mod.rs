@@ -5,6 +5,11 @@ mod mongo;
 #[cfg(feature = "log-mongo")]
 pub use mongo::*;
 
+#[cfg(feature = "log-sqlite")]
+mod sqlite;
+#[cfg(feature = "log-sqlite")]
+pub use sqlite::*;
+
 /// This enum serves as a registry of all loggers that are currently enabled, and are gated by feature flags.
 #[derive(Default, Clone)]
 pub enum LoggingOptions {
@@ -16,4 +21,8 @@ pub enum LoggingOptions {
     // #[cfg_attr(docsrs, doc(cfg(feature = "log-mongo")))]
     #[cfg(feature = "log-mongo")]
     Mongo(MongoOptions),
+
+    /// Log to a local SQLite database
+    #[cfg(feature = "log-sqlite")]
+    Sqlite(SqliteOptions),
 }
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+use derive_builder::Builder;
+
+/// Options for a SQLite-driven logging system
+#[derive(Clone, Debug, Builder)]
+#[builder(pattern = "owned")]
+pub struct SqliteOptions {
+    /// Path of the database file to log to. The file is created if it does not already exist.
+    #[builder(setter(into))]
+    pub path: PathBuf,
+}
@@ -19,13 +19,14 @@ use derive_builder::Builder;
 pub use dot::DotConvertible;
 
 // Export all of the program states
-pub use building::ProgramBuilder;
+pub use building::{NamespacedProgramBuilder, ProgramBuilder};
 pub use executed::Executed;
 pub use initialized::Initialized;
 
 use crate::channel::ChannelID;
 use crate::datastructures::Identifier;
 use crate::logging::LogFilter;
+use crate::view::TimeoutPolicy;
 use thiserror::Error;
 
 pub use crate::shim::RunMode;
@@ -69,6 +70,10 @@ pub struct InitializationOptions {
     /// Flavor inference (Section 6.4 of the DAM paper)
     #[builder(setter(into), default)]
     pub(super) run_flavor_inference: bool,
+
+    /// How long channel operations may wait on the other end of the channel, see [TimeoutPolicy]
+    #[builder(setter(into), default)]
+    pub(super) timeout_policy: TimeoutPolicy,
 }
 
 /// Various ways initializing a program can fail
@@ -85,6 +90,54 @@ pub enum InitializationError {
     /// All contexts must be registered
     #[error("Unregistered Node: {0}")]
     UnregisteredNode(Identifier),
+
+    /// Bounded channels must be able to hold at least one element
+    #[error("Zero capacity channel: {0:?}")]
+    ZeroCapacity(ChannelID),
+
+    /// Initial tokens must fit within the capacity of the channel
+    #[error("Too many initial tokens on channel {channel:?}: {tokens} tokens with a capacity of {capacity}")]
+    TooManyInitialTokens {
+        /// The channel being initialized
+        channel: ChannelID,
+        /// The number of tokens requested
+        tokens: usize,
+        /// The capacity of the channel
+        capacity: usize,
+    },
+
+    /// A context was attached to a different number of channels than it declared
+    #[error(
+        "Context {node} declared {declared} {direction} ports, but {connected} were connected"
+    )]
+    UnconnectedPort {
+        /// The context which declared its ports
+        node: Identifier,
+        /// Which ports were mismatched
+        direction: PortDirection,
+        /// The number of ports which were declared
+        declared: usize,
+        /// The number of channels which were attached
+        connected: usize,
+    },
+}
+
+/// The direction of a context's port, see [ProgramBuilder::declare_inputs] and [ProgramBuilder::declare_outputs].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PortDirection {
+    /// Channels that the context receives from
+    Input,
+    /// Channels that the context sends to
+    Output,
+}
+
+impl std::fmt::Display for PortDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortDirection::Input => write!(f, "input"),
+            PortDirection::Output => write!(f, "output"),
+        }
+    }
 }
 
 /// Various ways a program can fail
@@ -1,19 +1,24 @@
 use std::sync::Arc;
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::{channel::handle::ChannelHandle, context::Context, datastructures::Identifier};
+use crate::{
+    channel::{handle::ChannelHandle, ChannelID},
+    context::Context,
+    datastructures::{Identifiable, Identifier},
+};
 
-use super::InitializationError;
+use super::{InitializationError, PortDirection};
 
 #[derive(Default)]
 pub(super) struct ProgramData<'a> {
     pub(super) nodes: Vec<Box<dyn Context + 'a>>,
     pub(super) edges: Vec<Arc<dyn ChannelHandle + 'a>>,
     pub(super) void_edges: Vec<Arc<dyn ChannelHandle + 'a>>,
+    pub(super) declared_ports: FxHashMap<(Identifier, PortDirection), usize>,
 }
 
-impl ProgramData<'_> {
+impl<'a> ProgramData<'a> {
     pub(super) fn node_identifiers(&self) -> FxHashMap<Identifier, String> {
         self.nodes
             .iter()
@@ -22,20 +27,72 @@ impl ProgramData<'_> {
             .collect()
     }
 
+    pub(super) fn node_ids(&self) -> Vec<Identifier> {
+        self.nodes.iter().map(|node| node.id()).collect()
+    }
+
+    pub(super) fn node_by_id(&self, id: Identifier) -> Option<&(dyn Context + 'a)> {
+        self.nodes
+            .iter()
+            .find(|node| node.id() == id)
+            .map(|node| node.as_ref())
+    }
+
+    /// Lists every fully connected channel as a (sender, receiver, channel) triple.
+    pub(super) fn connections(&self) -> Vec<(Identifier, Identifier, ChannelID)> {
+        self.edges
+            .iter()
+            .filter_map(|edge| Some((edge.sender()?, edge.receiver()?, edge.id())))
+            .collect()
+    }
+
+    /// Top-level nodes for which neither the node nor any of its children are attached to a channel.
+    pub(super) fn isolated_nodes(&self) -> Vec<Identifier> {
+        let attached: FxHashSet<Identifier> = self
+            .edges
+            .iter()
+            .chain(self.void_edges.iter())
+            .flat_map(|edge| edge.sender().into_iter().chain(edge.receiver()))
+            .collect();
+        self.nodes
+            .iter()
+            .filter(|node| {
+                node.ids()
+                    .into_iter()
+                    .flat_map(|(id, children)| std::iter::once(id).chain(children))
+                    .all(|id| !attached.contains(&id.id))
+            })
+            .map(|node| node.id())
+            .collect()
+    }
+
     pub(super) fn check(&self) -> Result<(), InitializationError> {
+        match self.validate().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Collects every issue with the program, instead of stopping at the first one.
+    pub(super) fn validate(&self) -> Vec<InitializationError> {
+        let mut issues = vec![];
+
         // Make sure that all edges have registered endpoints.
         for edge in &self.edges {
             if edge.sender().is_none() {
-                return Err(InitializationError::DisconnectedSender(edge.id()));
+                issues.push(InitializationError::DisconnectedSender(edge.id()));
             }
             if edge.receiver().is_none() {
-                return Err(InitializationError::DisconnectedReceiver(edge.id()));
+                issues.push(InitializationError::DisconnectedReceiver(edge.id()));
+            }
+            if edge.spec().capacity() == Some(0) {
+                issues.push(InitializationError::ZeroCapacity(edge.id()));
             }
         }
 
         for edge in &self.void_edges {
             if edge.sender().is_none() {
-                return Err(InitializationError::DisconnectedSender(edge.id()));
+                issues.push(InitializationError::DisconnectedSender(edge.id()));
             }
             if let Some(recv) = edge.receiver() {
                 // This is a panic because it should NEVER happen.
@@ -48,11 +105,35 @@ impl ProgramData<'_> {
         for edge in self.edges.iter().chain(self.void_edges.iter()) {
             for id in edge.sender().iter().chain(edge.receiver().iter()) {
                 if !all_node_ids.contains_key(id) {
-                    return Err(InitializationError::UnregisteredNode(*id));
+                    issues.push(InitializationError::UnregisteredNode(*id));
                 }
             }
         }
 
-        Ok(())
+        for (&(node, direction), &declared) in &self.declared_ports {
+            let connected = match direction {
+                PortDirection::Input => self
+                    .edges
+                    .iter()
+                    .filter(|edge| edge.receiver() == Some(node))
+                    .count(),
+                PortDirection::Output => self
+                    .edges
+                    .iter()
+                    .chain(self.void_edges.iter())
+                    .filter(|edge| edge.sender() == Some(node))
+                    .count(),
+            };
+            if connected != declared {
+                issues.push(InitializationError::UnconnectedPort {
+                    node,
+                    direction,
+                    declared,
+                    connected,
+                });
+            }
+        }
+
+        issues
     }
 }
@@ -0,0 +1,374 @@
+//! Helpers for writing tests against DAM programs.
+
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::{
+    channel::{ChannelElement, Receiver, Sender},
+    context::Context,
+    datastructures::Time,
+    shim::Mutex,
+    simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+    types::DAMType,
+    utility_contexts::FunctionContext,
+    view::{ContextView, TimeManager, TimeViewable},
+};
+
+/// The first point at which a channel diverged from the expected sequence.
+/// A `None` in `got` means the channel closed early, while a `None` in `expected` means the element was not expected at all.
+#[derive(Error, Debug, Clone)]
+#[error("Sequence mismatch at index {index}: expected {expected:?}, got {got:?}")]
+pub struct SequenceMismatch<T: core::fmt::Debug> {
+    /// Position in the received sequence
+    pub index: usize,
+    /// The element that was expected at this position
+    pub expected: Option<ChannelElement<T>>,
+    /// The element that was actually received
+    pub got: Option<ChannelElement<T>>,
+}
+
+fn matches<T: PartialEq>(a: &ChannelElement<T>, b: &ChannelElement<T>) -> bool {
+    a.time == b.time && a.data == b.data
+}
+
+impl<T: DAMType + PartialEq> Receiver<T> {
+    /// Dequeues elements, checking that they match the expected sequence exactly (both timestamp and data).
+    /// Returns the first mismatch, if any.
+    pub fn expect_sequence(
+        &self,
+        manager: &TimeManager,
+        expected: impl IntoIterator<Item = ChannelElement<T>>,
+    ) -> Result<(), SequenceMismatch<T>> {
+        for (index, expected) in expected.into_iter().enumerate() {
+            match self.dequeue(manager) {
+                Ok(got) if matches(&got, &expected) => {}
+                got => {
+                    return Err(SequenceMismatch {
+                        index,
+                        expected: Some(expected),
+                        got: got.ok(),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Dequeues as many elements as are expected, checking that the same multiset of elements is delivered in any order.
+    pub fn expect_sequence_unordered(
+        &self,
+        manager: &TimeManager,
+        expected: impl IntoIterator<Item = ChannelElement<T>>,
+    ) -> Result<(), SequenceMismatch<T>> {
+        let mut remaining: Vec<_> = expected.into_iter().collect();
+        let mut index = 0;
+        while !remaining.is_empty() {
+            match self.dequeue(manager) {
+                Ok(got) => match remaining.iter().position(|elem| matches(elem, &got)) {
+                    Some(position) => {
+                        remaining.swap_remove(position);
+                    }
+                    None => {
+                        return Err(SequenceMismatch {
+                            index,
+                            expected: None,
+                            got: Some(got),
+                        })
+                    }
+                },
+                Err(_) => {
+                    return Err(SequenceMismatch {
+                        index,
+                        expected: remaining.into_iter().next(),
+                        got: None,
+                    })
+                }
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
+type OutputBuffer<T> = Arc<Mutex<Vec<ChannelElement<T>>>>;
+
+/// Wires up a single context under test, feeding its inputs from pre-defined elements and collecting everything it outputs.
+/// ```ignore
+/// let mut harness = ChannelHarness::default();
+/// let input = harness.input(4, elements);
+/// let output = harness.output(4);
+/// let result = harness.run(MyContext::new(input, output));
+/// ```
+pub struct ChannelHarness<'a, T: Clone> {
+    builder: ProgramBuilder<'a>,
+    outputs: Vec<OutputBuffer<T>>,
+}
+
+impl<T: Clone> Default for ChannelHarness<'_, T> {
+    fn default() -> Self {
+        Self {
+            builder: Default::default(),
+            outputs: vec![],
+        }
+    }
+}
+
+/// The outcome of running a context in a [ChannelHarness].
+#[derive(Debug)]
+pub struct HarnessResult<T> {
+    /// Everything received on each output, in the order the outputs were created
+    pub outputs: Vec<Vec<ChannelElement<T>>>,
+
+    /// The time of the context under test when it finished
+    pub final_tick: Time,
+
+    /// Whether the simulation completed without errors
+    pub passed: bool,
+}
+
+impl<'a, T: DAMType + 'a> ChannelHarness<'a, T> {
+    /// Creates an input channel for the context under test, which the harness fills with the given elements.
+    pub fn input(
+        &mut self,
+        capacity: usize,
+        elements: impl IntoIterator<Item = ChannelElement<T>> + Send + Sync + 'a,
+    ) -> Receiver<T> {
+        let (snd, rcv) = self.builder.bounded(capacity);
+        let mut driver = FunctionContext::default();
+        snd.attach_sender(&driver);
+        driver.set_run(move |time| {
+            for element in elements {
+                if snd.enqueue(time, element).is_err() {
+                    return;
+                }
+            }
+        });
+        self.builder.add_child(driver);
+        rcv
+    }
+
+    /// Creates an output channel for the context under test, whose contents are collected by the harness.
+    pub fn output(&mut self, capacity: usize) -> Sender<T> {
+        let (snd, rcv) = self.builder.bounded(capacity);
+        let buffer = OutputBuffer::default();
+        self.outputs.push(buffer.clone());
+
+        let mut collector = FunctionContext::default();
+        rcv.attach_receiver(&collector);
+        collector.set_run(move |time| {
+            while let Ok(element) = rcv.dequeue(time) {
+                buffer.lock().unwrap().push(element);
+            }
+        });
+        self.builder.add_child(collector);
+        snd
+    }
+
+    /// Runs the context to completion, returning everything it output.
+    pub fn run(mut self, ctx: impl Context + 'a) -> HarnessResult<T> {
+        let view = ctx.view();
+        self.builder.add_child(ctx);
+        let executed = self
+            .builder
+            .initialize(InitializationOptions::default())
+            .expect("Failed to initialize the harness")
+            .run(RunOptions::default());
+
+        HarnessResult {
+            outputs: self
+                .outputs
+                .into_iter()
+                .map(|buffer| std::mem::take(&mut *buffer.lock().unwrap()))
+                .collect(),
+            // The context is marked as done once it finishes, so keep only the actual timestamp.
+            final_tick: Time::new(view.tick_lower_bound().time()),
+            passed: executed.passed(),
+        }
+    }
+}
+
+/// Ways in which repeated runs of a program can fail to behave deterministically.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DeterminismError {
+    /// A run did not complete successfully
+    #[error("Run {run} failed")]
+    Failed {
+        /// The index of the failing run
+        run: usize,
+    },
+
+    /// A run disagreed with the first run
+    #[error("Run {run} diverged from the first run at context {index}: expected {expected:?}, got {got:?}")]
+    Diverged {
+        /// The index of the diverging run
+        run: usize,
+        /// The position of the first differing context, in order of construction
+        index: usize,
+        /// The (name, final tick) of the context in the first run
+        expected: Option<(String, u64)>,
+        /// The (name, final tick) of the context in the diverging run
+        got: Option<(String, u64)>,
+    },
+}
+
+/// Builds and runs a program `runs` times, checking that every run passes and that each context finishes at the same tick as in the first run.
+/// Contexts are matched up across runs in the order in which they were constructed.
+pub fn check_deterministic<'a>(
+    runs: usize,
+    mut make: impl FnMut() -> ProgramBuilder<'a>,
+) -> Result<(), DeterminismError> {
+    let mut reference: Option<Vec<(String, u64)>> = None;
+    for run in 0..runs {
+        let executed = make()
+            .initialize(InitializationOptions::default())
+            .expect("Failed to initialize the program")
+            .run(RunOptions::default());
+        if !executed.passed() {
+            return Err(DeterminismError::Failed { run });
+        }
+
+        let mut summaries: Vec<_> = executed.summaries().iter().collect();
+        summaries.sort_by_key(|summary| summary.id.id.id);
+        let outcome: Vec<_> = summaries
+            .into_iter()
+            .map(|summary| (summary.id.name.clone(), summary.max_time()))
+            .collect();
+
+        match &reference {
+            None => reference = Some(outcome),
+            Some(expected) => {
+                let length = expected.len().max(outcome.len());
+                if let Some(index) = (0..length).find(|&i| expected.get(i) != outcome.get(i)) {
+                    return Err(DeterminismError::Diverged {
+                        run,
+                        index,
+                        expected: expected.get(index).cloned(),
+                        got: outcome.get(index).cloned(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Panicking version of [check_deterministic], for use in tests.
+pub fn assert_deterministic<'a>(runs: usize, make: impl FnMut() -> ProgramBuilder<'a>) {
+    if let Err(err) = check_deterministic(runs, make) {
+        panic!("{err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_deterministic, check_deterministic, ChannelHarness, DeterminismError};
+    use crate::{
+        channel::ChannelElement,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        structures::Time,
+        utility_contexts::{FunctionContext, GeneratorContext},
+    };
+
+    #[test]
+    fn test_harness() {
+        let mut harness = ChannelHarness::default();
+        let input = harness.input(
+            4,
+            (0..8u64).map(|i| ChannelElement::new(Time::new(i + 1), i)),
+        );
+        let output = harness.output(4);
+
+        let mut doubler = FunctionContext::default();
+        input.attach_receiver(&doubler);
+        output.attach_sender(&doubler);
+        doubler.set_run(move |time| {
+            while let Ok(element) = input.dequeue(time) {
+                output
+                    .enqueue(time, ChannelElement::new(time.tick() + 1, element.data * 2))
+                    .unwrap();
+            }
+        });
+
+        let result = harness.run(doubler);
+        assert!(result.passed);
+        assert_eq!(result.outputs.len(), 1);
+        let outputs: Vec<_> = result.outputs[0].iter().map(|elem| elem.data).collect();
+        assert_eq!(outputs, (0..8u64).map(|i| i * 2).collect::<Vec<_>>());
+        assert_eq!(result.final_tick, Time::new(8));
+    }
+
+    fn make_pipeline(extra_delay: u64) -> ProgramBuilder<'static> {
+        let mut builder = ProgramBuilder::default();
+        let (snd, rcv) = builder.bounded(2);
+        builder.add_child(GeneratorContext::new(|| 0..16u32, snd));
+        let mut consumer = FunctionContext::default();
+        rcv.attach_receiver(&consumer);
+        consumer.set_run(move |time| {
+            while rcv.dequeue(time).is_ok() {
+                time.incr_cycles(1 + extra_delay);
+            }
+        });
+        builder.add_child(consumer);
+        builder
+    }
+
+    #[test]
+    fn test_deterministic() {
+        assert_deterministic(4, || make_pipeline(0));
+    }
+
+    #[test]
+    fn test_nondeterministic() {
+        // Shared state which leaks between runs, making each run slower than the last.
+        let runs = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let result = check_deterministic(3, || {
+            let mut count = runs.lock().unwrap();
+            *count += 1;
+            make_pipeline(*count)
+        });
+        assert!(matches!(
+            result,
+            Err(DeterminismError::Diverged { run: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_expect_sequence() {
+        let mut parent = ProgramBuilder::default();
+        let (ordered_snd, ordered_rcv) = parent.unbounded();
+        let (unordered_snd, unordered_rcv) = parent.unbounded();
+        parent.add_child(GeneratorContext::new(|| 0..4u32, ordered_snd));
+        parent.add_child(GeneratorContext::new(|| 0..4u32, unordered_snd));
+
+        let mut checker = FunctionContext::default();
+        ordered_rcv.attach_receiver(&checker);
+        unordered_rcv.attach_receiver(&checker);
+        checker.set_run(move |time| {
+            // Generators emit element i at time i + 1.
+            let expected =
+                |order: [u32; 4]| order.map(|i| ChannelElement::new(Time::new(i as u64 + 1), i));
+
+            let err = ordered_rcv
+                .expect_sequence(time, expected([0, 1, 3, 2]))
+                .unwrap_err();
+            assert_eq!(err.index, 2);
+            assert_eq!(err.got.unwrap().data, 2);
+
+            unordered_rcv
+                .expect_sequence_unordered(time, expected([3, 1, 0, 2]))
+                .unwrap();
+            let err = unordered_rcv
+                .expect_sequence_unordered(time, expected([0]))
+                .unwrap_err();
+            assert!(err.got.is_none());
+        });
+        parent.add_child(checker);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
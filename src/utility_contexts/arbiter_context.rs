@@ -0,0 +1,211 @@
+use dam_macros::context_internal;
+
+use crate::{
+    channel::{
+        utils::{EventTime, Peekable},
+        ChannelElement, PeekResult, Receiver, Sender,
+    },
+    datastructures::Time,
+    types::DAMType,
+};
+
+use crate::context::Context;
+
+/// How an [ArbiterContext] picks between requests which are ready at the same time.
+#[derive(Clone, Copy, Debug)]
+pub enum ArbiterPolicy<T> {
+    /// Rotate priority, starting after the requestor which was granted last.
+    RoundRobin,
+
+    /// Requestors with lower indices always win.
+    FixedPriority,
+
+    /// The request with the earliest deadline wins, with ties going to the lowest index.
+    EarliestDeadlineFirst(fn(&T) -> Time),
+}
+
+/// Models an arbiter, which accepts requests from several requestors and grants them one at a time.
+/// A request from input `i` is forwarded to output `i` once granted, after which the arbiter is busy for `grant_latency` cycles.
+#[context_internal]
+pub struct ArbiterContext<T: Clone> {
+    inputs: Vec<Receiver<T>>,
+    outputs: Vec<Sender<T>>,
+    policy: ArbiterPolicy<T>,
+    grant_latency: u64,
+    next_index: usize,
+}
+
+impl<T: DAMType> ArbiterContext<T> {
+    /// Constructs an arbiter. Requests from `inputs[i]` are granted onto `outputs[i]`.
+    pub fn new(
+        inputs: Vec<Receiver<T>>,
+        outputs: Vec<Sender<T>>,
+        policy: ArbiterPolicy<T>,
+        grant_latency: u64,
+    ) -> Self {
+        assert_eq!(
+            inputs.len(),
+            outputs.len(),
+            "Each requestor needs a grant channel"
+        );
+        let x = Self {
+            inputs,
+            outputs,
+            policy,
+            grant_latency,
+            next_index: 0,
+            context_info: Default::default(),
+        };
+        x.inputs.iter().for_each(|input| input.attach_receiver(&x));
+        x.outputs.iter().for_each(|output| output.attach_sender(&x));
+        x
+    }
+
+    fn choose(&self, candidates: &[usize]) -> usize {
+        match self.policy {
+            ArbiterPolicy::FixedPriority => candidates[0],
+            ArbiterPolicy::RoundRobin => *candidates
+                .iter()
+                .find(|ind| **ind >= self.next_index)
+                .unwrap_or(&candidates[0]),
+            ArbiterPolicy::EarliestDeadlineFirst(deadline) => *candidates
+                .iter()
+                .min_by_key(|ind| match self.inputs[**ind].peek() {
+                    PeekResult::Something(element) => deadline(&element.data),
+                    _ => unreachable!("Candidates always have an element available"),
+                })
+                .unwrap(),
+        }
+    }
+}
+
+impl<T: DAMType> Context for ArbiterContext<T> {
+    fn run_falliable(&mut self) -> anyhow::Result<()> {
+        loop {
+            let now = self.time.tick();
+            let events: Vec<EventTime> =
+                self.inputs.iter().map(|input| input.next_event()).collect();
+            let candidates: Vec<usize> = events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| matches!(event, EventTime::Ready(time) if *time <= now))
+                .map(|(ind, _)| ind)
+                .collect();
+
+            if candidates.is_empty() {
+                match events.into_iter().min() {
+                    Some(EventTime::Ready(time)) => self.time.advance(time),
+                    Some(EventTime::Nothing(time)) => self.time.advance(time + 1),
+                    Some(EventTime::Closed) | None => return Ok(()),
+                }
+                continue;
+            }
+
+            let winner = self.choose(&candidates);
+            self.next_index = (winner + 1) % self.inputs.len();
+            let ChannelElement { data, .. } = self.inputs[winner].dequeue(&self.time)?;
+            self.outputs[winner].enqueue(
+                &self.time,
+                ChannelElement::new(self.time.tick() + self.grant_latency, data),
+            )?;
+            self.time.incr_cycles(self.grant_latency);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{ArbiterContext, ArbiterPolicy};
+
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    const REQUESTORS: usize = 4;
+
+    /// Returns the grant times seen by each requestor.
+    fn run_arbiter(policy: ArbiterPolicy<u64>, requests_per_requestor: u64) -> Vec<Vec<Time>> {
+        let mut parent = ProgramBuilder::default();
+        let grants = Arc::new(Mutex::new(vec![vec![]; REQUESTORS]));
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        for requestor in 0..REQUESTORS {
+            // Requests are all available up front, so that every requestor is always waiting.
+            let (req_snd, req_rcv) = parent.unbounded();
+            let (grant_snd, grant_rcv) = parent.bounded(2);
+            inputs.push(req_rcv);
+            outputs.push(grant_snd);
+
+            let mut source = FunctionContext::default();
+            req_snd.attach_sender(&source);
+            source.set_run(move |time| {
+                for iter in 0..requests_per_requestor {
+                    req_snd
+                        .enqueue(time, ChannelElement::new(time.tick(), iter))
+                        .unwrap();
+                }
+            });
+            parent.add_child(source);
+
+            let mut sink = FunctionContext::default();
+            grant_rcv.attach_receiver(&sink);
+            let grants = grants.clone();
+            sink.set_run(move |time| {
+                for iter in 0..requests_per_requestor {
+                    let grant = grant_rcv.dequeue(time).unwrap();
+                    assert_eq!(grant.data, iter);
+                    grants.lock().unwrap()[requestor].push(grant.time);
+                }
+                assert!(grant_rcv.dequeue(time).is_err());
+            });
+            parent.add_child(sink);
+        }
+        parent.add_child(ArbiterContext::new(inputs, outputs, policy, 1));
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+        let grants = grants.lock().unwrap();
+        grants.clone()
+    }
+
+    #[test]
+    fn test_round_robin_no_starvation() {
+        let grants = run_arbiter(ArbiterPolicy::RoundRobin, 2500);
+        assert_eq!(grants.iter().map(Vec::len).sum::<usize>(), 10000);
+        for times in grants {
+            // Each requestor is granted at least once every REQUESTORS cycles.
+            assert!(times
+                .windows(2)
+                .all(|pair| pair[1].time() - pair[0].time() <= REQUESTORS as u64));
+        }
+    }
+
+    #[test]
+    fn test_fixed_priority() {
+        let grants = run_arbiter(ArbiterPolicy::FixedPriority, 16);
+        // Each requestor is only served once all higher priority requestors are done.
+        for pair in grants.windows(2) {
+            assert!(pair[0].last().unwrap() < pair[1].first().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_earliest_deadline_first() {
+        // Later requests have earlier deadlines, so whichever requestor is granted first keeps winning until it is done.
+        let grants = run_arbiter(
+            ArbiterPolicy::EarliestDeadlineFirst(|iter| Time::new(100 - iter)),
+            8,
+        );
+        for pair in grants.windows(2) {
+            assert!(pair[0].last().unwrap() < pair[1].first().unwrap());
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::VecDeque;
+
+use dam_macros::context_internal;
+
+use crate::{
+    channel::{
+        utils::{EventTime, Peekable},
+        ChannelElement, Receiver, Sender,
+    },
+    types::DAMType,
+};
+
+use crate::context::Context;
+
+/// Models a pipelined hardware FIFO with a fixed depth.
+/// Every cycle, up to `throughput_per_tick` elements are emitted from the head of the queue,
+/// after which up to `throughput_per_tick` elements are accepted from the input, as long as there is room.
+/// An accepted element can be emitted on the following cycle at the earliest, and arrives one cycle after being emitted.
+#[context_internal]
+pub struct FIFOContext<T: Clone> {
+    input: Receiver<T>,
+    output: Sender<T>,
+    depth: usize,
+    throughput_per_tick: usize,
+    queue: VecDeque<ChannelElement<T>>,
+}
+
+impl<T: DAMType> FIFOContext<T> {
+    /// Constructs a FIFO holding at most `depth` elements, which accepts and emits up to `throughput_per_tick` elements per cycle.
+    pub fn new(
+        input: Receiver<T>,
+        output: Sender<T>,
+        depth: usize,
+        throughput_per_tick: usize,
+    ) -> Self {
+        assert!(
+            depth > 0,
+            "A FIFO must be able to hold at least one element"
+        );
+        assert!(throughput_per_tick > 0, "A FIFO must make progress");
+        let x = Self {
+            input,
+            output,
+            depth,
+            throughput_per_tick,
+            queue: VecDeque::with_capacity(depth),
+            context_info: Default::default(),
+        };
+        x.input.attach_receiver(&x);
+        x.output.attach_sender(&x);
+        x
+    }
+}
+
+impl<T: DAMType> Context for FIFOContext<T> {
+    fn run_falliable(&mut self) -> anyhow::Result<()> {
+        loop {
+            let now = self.time.tick();
+
+            let mut emitted = 0;
+            while emitted < self.throughput_per_tick
+                && self.queue.front().is_some_and(|head| head.time <= now)
+            {
+                let ChannelElement { data, .. } = self.queue.pop_front().unwrap();
+                self.output
+                    .enqueue(&self.time, ChannelElement::new(now + 1, data))?;
+                emitted += 1;
+            }
+
+            let mut accepted = 0;
+            while accepted < self.throughput_per_tick && self.queue.len() < self.depth {
+                match self.input.next_event() {
+                    EventTime::Ready(time) if time <= now => {
+                        let ChannelElement { data, .. } = self.input.dequeue(&self.time)?;
+                        self.queue.push_back(ChannelElement::new(now + 1, data));
+                        accepted += 1;
+                    }
+                    _ => break,
+                }
+            }
+
+            if !self.queue.is_empty() || accepted > 0 {
+                self.time.incr_cycles(1);
+                continue;
+            }
+
+            match self.input.next_event() {
+                EventTime::Ready(time) => self.time.advance(time),
+                EventTime::Nothing(time) => self.time.advance(time + 1),
+                EventTime::Closed => return Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FIFOContext;
+
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_bursty_fifo() {
+        const BURSTS: u64 = 4;
+        const BURST_SIZE: u64 = 8;
+        const BURST_PERIOD: u64 = 10;
+        let burst_start = |burst: u64| BURST_PERIOD * (burst + 1);
+
+        let mut parent = ProgramBuilder::default();
+        let (in_snd, in_rcv) = parent.unbounded();
+        let (out_snd, out_rcv) = parent.bounded(8);
+
+        let mut source = FunctionContext::default();
+        in_snd.attach_sender(&source);
+        source.set_run(move |time| {
+            for burst in 0..BURSTS {
+                for iter in 0..BURST_SIZE {
+                    let element = ChannelElement::new(
+                        Time::new(burst_start(burst)),
+                        burst * BURST_SIZE + iter,
+                    );
+                    in_snd.enqueue(time, element).unwrap();
+                }
+            }
+        });
+        parent.add_child(source);
+
+        parent.add_child(FIFOContext::new(in_rcv, out_snd, 4, 2));
+
+        let mut sink = FunctionContext::default();
+        out_rcv.attach_receiver(&sink);
+        sink.set_run(move |time| {
+            for burst in 0..BURSTS {
+                for iter in 0..BURST_SIZE {
+                    let element = out_rcv.dequeue(time).unwrap();
+                    assert_eq!(element.data, burst * BURST_SIZE + iter);
+                    // Two elements are accepted per cycle, each spending a cycle in the FIFO and a cycle in flight.
+                    assert_eq!(element.time, Time::new(burst_start(burst) + 2 + iter / 2));
+                }
+            }
+            assert!(out_rcv.dequeue(time).is_err());
+        });
+        parent.add_child(sink);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_fifo_backpressure() {
+        const NUM_ELEMENTS: u64 = 16;
+        const DEPTH: usize = 4;
+        const SINK_PERIOD: u64 = 10;
+
+        let mut parent = ProgramBuilder::default();
+        let (in_snd, in_rcv) = parent.bounded(1);
+        let (out_snd, out_rcv) = parent.bounded(1);
+
+        let mut source = FunctionContext::default();
+        in_snd.attach_sender(&source);
+        source.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                in_snd
+                    .enqueue(time, ChannelElement::new(Time::new(1), iter))
+                    .unwrap();
+            }
+            // Besides the FIFO itself, only the two channels and the element the FIFO is emitting can hold elements,
+            // so the final sends had to wait for the sink to make room.
+            let buffered = DEPTH as u64 + 3;
+            assert!(time.tick() >= Time::new((NUM_ELEMENTS - buffered - 1) * SINK_PERIOD));
+        });
+        parent.add_child(source);
+
+        parent.add_child(FIFOContext::new(in_rcv, out_snd, DEPTH, 1));
+
+        let mut sink = FunctionContext::default();
+        out_rcv.attach_receiver(&sink);
+        sink.set_run(move |time| {
+            let mut last = Time::new(0);
+            for iter in 0..NUM_ELEMENTS {
+                let element = out_rcv.dequeue(time).unwrap();
+                assert_eq!(element.data, iter);
+                assert!(element.time >= last);
+                last = element.time;
+                time.incr_cycles(SINK_PERIOD);
+            }
+            assert!(out_rcv.dequeue(time).is_err());
+        });
+        parent.add_child(sink);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
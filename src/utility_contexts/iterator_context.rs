@@ -0,0 +1,192 @@
+use dam_macros::context_internal;
+
+use crate::context_tools::*;
+
+use crate::context::Context;
+use crate::view::TimeManager;
+
+use super::UtilityError;
+
+/// The conversion function of an [IteratorContext] whose items are already elements, as used by [IteratorContext::from_vec] and [IteratorContext::from_fn].
+pub type PassThrough<U> = fn(ChannelElement<U>, &mut TimeManager) -> ChannelElement<U>;
+
+/// A context which sends an element to a channel for each item of an iterator.
+/// Unlike [super::GeneratorContext], the conversion function controls each element's timestamp, and may advance time as needed.
+#[context_internal]
+pub struct IteratorContext<U: Clone, IType, FType>
+where
+    IType: IntoIterator + Send + Sync,
+    FType: FnMut(IType::Item, &mut TimeManager) -> ChannelElement<U> + Send + Sync,
+{
+    iterator: Option<IType>,
+    func: FType,
+    output: Sender<U>,
+}
+
+impl<U: DAMType, IType, FType> Context for IteratorContext<U, IType, FType>
+where
+    IType: IntoIterator + Send + Sync,
+    FType: FnMut(IType::Item, &mut TimeManager) -> ChannelElement<U> + Send + Sync,
+{
+    fn run_falliable(&mut self) -> anyhow::Result<()> {
+        if let Some(iterator) = self.iterator.take() {
+            for val in iterator {
+                let element = (self.func)(val, &mut self.time);
+                self.output.enqueue(&self.time, element)?;
+            }
+        } else {
+            Err(UtilityError::DuplicateExec)?
+        }
+        Ok(())
+    }
+}
+
+impl<U: DAMType, IType, FType> IteratorContext<U, IType, FType>
+where
+    IType: IntoIterator + Send + Sync,
+    FType: FnMut(IType::Item, &mut TimeManager) -> ChannelElement<U> + Send + Sync,
+{
+    /// Constructs an IteratorContext from an iterator, a conversion function, and the output channel
+    pub fn new(iterator: IType, func: FType, output: Sender<U>) -> Self {
+        let ic = IteratorContext {
+            iterator: Some(iterator),
+            func,
+            output,
+            context_info: Default::default(),
+        };
+        ic.output.attach_sender(&ic);
+        ic
+    }
+}
+
+impl<U: DAMType> IteratorContext<U, Vec<ChannelElement<U>>, PassThrough<U>> {
+    /// Constructs an IteratorContext which sends a pre-computed sequence of elements, in order.
+    pub fn from_vec(elements: Vec<ChannelElement<U>>, output: Sender<U>) -> Self {
+        Self::new(elements, |element, _| element, output)
+    }
+}
+
+impl<U: DAMType, GType> IteratorContext<U, std::iter::FromFn<GType>, PassThrough<U>>
+where
+    GType: FnMut() -> Option<ChannelElement<U>> + Send + Sync,
+{
+    /// Constructs an IteratorContext which repeatedly calls a closure, sending each element it produces until it returns `None`.
+    pub fn from_fn(generator: GType, output: Sender<U>) -> Self {
+        Self::new(std::iter::from_fn(generator), |element, _| element, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IteratorContext;
+
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::{CheckerContext, FunctionContext},
+    };
+
+    #[test]
+    fn test_iterator_context() {
+        let mut parent = ProgramBuilder::default();
+        let (snd, rcv) = parent.bounded(8);
+
+        parent.add_child(IteratorContext::new(
+            (0..1000u64).map(|i| ChannelElement::new(Time::new(i), i as u32)),
+            |element, _| element,
+            snd,
+        ));
+        parent.add_child(CheckerContext::new(|| 0..1000u32, rcv));
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_iterator_context_from_fn() {
+        const NUM_ELEMENTS: u64 = 1000;
+        let mut parent = ProgramBuilder::default();
+        let (snd, rcv) = parent.bounded(8);
+
+        let mut iter = 0;
+        parent.add_child(IteratorContext::from_fn(
+            move || {
+                iter += 1;
+                (iter <= NUM_ELEMENTS).then(|| ChannelElement::new(Time::new(iter * 2), iter))
+            },
+            snd,
+        ));
+        let mut checker = FunctionContext::default();
+        rcv.attach_receiver(&checker);
+        checker.set_run(move |time| {
+            for iter in 1..=NUM_ELEMENTS {
+                let element = rcv.dequeue(time).unwrap();
+                assert_eq!(element.data, iter);
+                assert_eq!(element.time, Time::new(iter * 2));
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        parent.add_child(checker);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_iterator_context_from_vec() {
+        let mut parent = ProgramBuilder::default();
+        let (snd, rcv) = parent.bounded(8);
+
+        let elements = (0..1000u32)
+            .map(|i| ChannelElement::new(Time::new(i as u64), i))
+            .collect();
+        parent.add_child(IteratorContext::from_vec(elements, snd));
+        parent.add_child(CheckerContext::new(|| 0..1000u32, rcv));
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_iterator_context_advances_time() {
+        let mut parent = ProgramBuilder::default();
+        let (snd, rcv) = parent.unbounded();
+
+        // Each element takes as many cycles to produce as its value.
+        parent.add_child(IteratorContext::new(
+            vec![3u64, 1, 4, 1, 5],
+            |cycles, time| {
+                time.incr_cycles(cycles);
+                ChannelElement::new(time.tick(), cycles)
+            },
+            snd,
+        ));
+
+        let mut checker = FunctionContext::default();
+        rcv.attach_receiver(&checker);
+        checker.set_run(move |time| {
+            let times: Vec<_> = std::iter::from_fn(|| rcv.dequeue(time).ok())
+                .map(|element| element.time.time())
+                .collect();
+            // Elements are sent no earlier than one tick after they are produced.
+            assert_eq!(times, vec![4, 5, 9, 10, 15]);
+        });
+        parent.add_child(checker);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+
+use dam_macros::context_internal;
+
+use crate::{
+    channel::{
+        utils::{EventTime, Peekable},
+        ChannelElement, Receiver, Sender,
+    },
+    datastructures::Time,
+    types::{DAMType, StaticallySized},
+};
+
+use crate::context::Context;
+
+use super::UtilityError;
+
+/// A request to read a single entry of a [MemoryContext].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReadRequest {
+    /// The address to read
+    pub addr: usize,
+}
+
+impl StaticallySized for ReadRequest {
+    const SIZE: usize = usize::SIZE;
+}
+
+/// A request to overwrite a single entry of a [MemoryContext].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WriteRequest<T> {
+    /// The address to write
+    pub addr: usize,
+    /// The value to write
+    pub data: T,
+}
+
+impl<T: DAMType> DAMType for WriteRequest<T> {
+    fn dam_size(&self) -> usize {
+        usize::SIZE + self.data.dam_size()
+    }
+}
+
+/// Models a single-bank memory (such as an SRAM) with one read port and one write port.
+/// Each port accepts one request per cycle. Reads are answered `read_latency` cycles after they are accepted,
+/// and writes become visible to reads `write_latency` cycles after they are accepted.
+/// If a read and a write are visible in the same cycle, the read observes the write.
+/// Banked memories can be modeled by constructing one context per bank.
+#[context_internal]
+pub struct MemoryContext<T: Clone> {
+    storage: Vec<T>,
+    read_req: Receiver<ReadRequest>,
+    write_req: Receiver<WriteRequest<T>>,
+    read_resp: Sender<T>,
+    read_latency: u64,
+    write_latency: u64,
+    pending_writes: VecDeque<ChannelElement<WriteRequest<T>>>,
+}
+
+impl<T: DAMType> MemoryContext<T> {
+    /// Constructs a memory with the given initial contents, whose size is the size of the memory.
+    pub fn new(
+        contents: Vec<T>,
+        read_req: Receiver<ReadRequest>,
+        write_req: Receiver<WriteRequest<T>>,
+        read_resp: Sender<T>,
+        read_latency: u64,
+        write_latency: u64,
+    ) -> Self {
+        let x = Self {
+            storage: contents,
+            read_req,
+            write_req,
+            read_resp,
+            read_latency,
+            write_latency,
+            pending_writes: Default::default(),
+            context_info: Default::default(),
+        };
+        x.read_req.attach_receiver(&x);
+        x.write_req.attach_receiver(&x);
+        x.read_resp.attach_sender(&x);
+        x
+    }
+
+    fn check_addr(&self, addr: usize) -> Result<(), UtilityError> {
+        if addr < self.storage.len() {
+            Ok(())
+        } else {
+            Err(UtilityError::OutOfBounds {
+                addr,
+                size: self.storage.len(),
+            })
+        }
+    }
+
+    /// Applies all writes which have become visible by `time`.
+    fn commit_writes(&mut self, time: Time) {
+        while let Some(write) = self.pending_writes.front() {
+            if write.time > time {
+                return;
+            }
+            let ChannelElement { data, .. } = self.pending_writes.pop_front().unwrap();
+            self.storage[data.addr] = data.data;
+        }
+    }
+}
+
+impl<T: DAMType> Context for MemoryContext<T> {
+    fn run_falliable(&mut self) -> anyhow::Result<()> {
+        loop {
+            let now = self.time.tick();
+            let write_event = self.write_req.next_event();
+            let read_event = self.read_req.next_event();
+            let is_ready =
+                |event: EventTime| matches!(event, EventTime::Ready(time) if time <= now);
+
+            let write_ready = is_ready(write_event);
+            let read_ready = is_ready(read_event);
+
+            if write_ready {
+                let ChannelElement { data, .. } = self.write_req.dequeue(&self.time)?;
+                self.check_addr(data.addr)?;
+                self.pending_writes
+                    .push_back(ChannelElement::new(now + self.write_latency, data));
+            }
+
+            if read_ready {
+                let ChannelElement { data, .. } = self.read_req.dequeue(&self.time)?;
+                self.check_addr(data.addr)?;
+                self.commit_writes(now);
+                self.read_resp.enqueue(
+                    &self.time,
+                    ChannelElement::new(now + self.read_latency, self.storage[data.addr].clone()),
+                )?;
+            }
+
+            if write_ready || read_ready {
+                self.time.incr_cycles(1);
+                continue;
+            }
+
+            match std::cmp::min(write_event, read_event) {
+                EventTime::Ready(time) => self.time.advance(time),
+                EventTime::Nothing(time) => self.time.advance(time + 1),
+                EventTime::Closed => return Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoryContext, ReadRequest, WriteRequest};
+
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_memory() {
+        const SIZE: usize = 64;
+        const READ_LATENCY: u64 = 2;
+        const READ_START: u64 = 100;
+        let mut parent = ProgramBuilder::default();
+        let (write_snd, write_rcv) = parent.bounded(4);
+        let (read_snd, read_rcv) = parent.bounded(4);
+        let (resp_snd, resp_rcv) = parent.bounded(4);
+
+        let mut writer = FunctionContext::default();
+        write_snd.attach_sender(&writer);
+        writer.set_run(move |time| {
+            for addr in 0..SIZE {
+                let request = WriteRequest {
+                    addr,
+                    data: addr as u64 * 10,
+                };
+                write_snd
+                    .enqueue(time, ChannelElement::new(time.tick(), request))
+                    .unwrap();
+                time.incr_cycles(1);
+            }
+        });
+        parent.add_child(writer);
+
+        let mut reader = FunctionContext::default();
+        read_snd.attach_sender(&reader);
+        reader.set_run(move |time| {
+            // Reads happen well after all of the writes, in reverse order.
+            for iter in 0..SIZE {
+                let request = ReadRequest {
+                    addr: SIZE - 1 - iter,
+                };
+                read_snd
+                    .enqueue(
+                        time,
+                        ChannelElement::new(Time::new(READ_START + iter as u64), request),
+                    )
+                    .unwrap();
+            }
+        });
+        parent.add_child(reader);
+
+        let mut checker = FunctionContext::default();
+        resp_rcv.attach_receiver(&checker);
+        checker.set_run(move |time| {
+            for iter in 0..SIZE {
+                let response = resp_rcv.dequeue(time).unwrap();
+                assert_eq!(response.data, (SIZE - 1 - iter) as u64 * 10);
+                assert_eq!(
+                    response.time,
+                    Time::new(READ_START + iter as u64 + READ_LATENCY)
+                );
+            }
+        });
+        parent.add_child(checker);
+
+        parent.add_child(MemoryContext::new(
+            vec![0u64; SIZE],
+            read_rcv,
+            write_rcv,
+            resp_snd,
+            READ_LATENCY,
+            1,
+        ));
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
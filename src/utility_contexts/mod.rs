@@ -2,21 +2,38 @@
 //! These can be used to 'black-box' a lot of functionality, such as reading from a file for input, etc.
 
 mod approx_checker_context;
+mod arbiter_context;
 mod broadcast_context;
 mod checker_context;
 mod consumer_context;
+mod fifo_context;
 mod function_context;
 mod generator_context;
+mod iterator_context;
+mod memory_context;
+mod network_context;
+mod pipeline_context;
+mod reduce_context;
+mod split_context;
 mod trace_context;
 
 use std::fmt::Debug;
 
+pub use crate::channel::Either;
 pub use approx_checker_context::ApproxCheckerContext;
+pub use arbiter_context::{ArbiterContext, ArbiterPolicy};
 pub use broadcast_context::BroadcastContext;
 pub use checker_context::CheckerContext;
 pub use consumer_context::{ConsumerContext, PrinterContext};
+pub use fifo_context::FIFOContext;
 pub use function_context::FunctionContext;
 pub use generator_context::GeneratorContext;
+pub use iterator_context::{IteratorContext, PassThrough};
+pub use memory_context::{MemoryContext, ReadRequest, WriteRequest};
+pub use network_context::{NetworkContext, Routable, RoutingTable};
+pub use pipeline_context::{PipelineContext, PipelineStage};
+pub use reduce_context::ReduceContext;
+pub use split_context::SplitContext;
 use thiserror::Error;
 pub use trace_context::{random_trace, TraceContext};
 
@@ -37,6 +54,28 @@ pub enum UtilityError {
     /// A context was somehow executed twice
     #[error("Cannot execute a context more than once!")]
     DuplicateExec,
+
+    /// An element was headed to a destination which has no route
+    #[error("No route to destination: {0}")]
+    NoRoute(String),
+
+    /// A route pointed at an output port which does not exist
+    #[error("Port {port} does not exist, as there are only {ports} outputs")]
+    NoSuchPort {
+        /// The requested port
+        port: usize,
+        /// The number of output ports
+        ports: usize,
+    },
+
+    /// A memory access was outside of the memory
+    #[error("Address {addr} is out of bounds for a memory of size {size}")]
+    OutOfBounds {
+        /// The requested address
+        addr: usize,
+        /// The number of entries in the memory
+        size: usize,
+    },
 }
 
 /// Checker failures
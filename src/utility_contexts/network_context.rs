@@ -0,0 +1,282 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc};
+
+use dam_macros::context_internal;
+
+use crate::{
+    channel::{
+        group::{ReceiverGroup, TieBreak},
+        ChannelElement, Receiver, Sender,
+    },
+    shim::RwLock,
+    types::DAMType,
+};
+
+use crate::context::Context;
+
+use super::UtilityError;
+
+/// Elements which carry their own destination, so that they can be routed by a [NetworkContext].
+pub trait Routable {
+    /// The key used to look up the output port in a [RoutingTable].
+    type Destination: Eq + Hash + Clone + Debug + Send + Sync;
+
+    /// Where this element is headed.
+    fn destination(&self) -> Self::Destination;
+}
+
+/// A mapping from destinations to output ports.
+/// Clones share the same underlying table, so routes can be updated while the simulation is running.
+pub struct RoutingTable<D> {
+    routes: Arc<RwLock<HashMap<D, usize>>>,
+}
+
+impl<D> Clone for RoutingTable<D> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+        }
+    }
+}
+
+impl<D> Default for RoutingTable<D> {
+    fn default() -> Self {
+        Self {
+            routes: Default::default(),
+        }
+    }
+}
+
+impl<D: Eq + Hash> RoutingTable<D> {
+    /// Routes elements headed to `destination` out of `port`, returning the previous port if there was one.
+    pub fn insert(&self, destination: D, port: usize) -> Option<usize> {
+        self.routes.write().unwrap().insert(destination, port)
+    }
+
+    /// Removes the route for `destination`, returning its port if there was one.
+    pub fn remove(&self, destination: &D) -> Option<usize> {
+        self.routes.write().unwrap().remove(destination)
+    }
+
+    /// The output port for `destination`, if a route exists.
+    pub fn lookup(&self, destination: &D) -> Option<usize> {
+        self.routes.read().unwrap().get(destination).copied()
+    }
+}
+
+impl<D: Eq + Hash> FromIterator<(D, usize)> for RoutingTable<D> {
+    fn from_iter<I: IntoIterator<Item = (D, usize)>>(iter: I) -> Self {
+        Self {
+            routes: Arc::new(RwLock::new(iter.into_iter().collect())),
+        }
+    }
+}
+
+/// Models a router, which takes elements from whichever input is ready first and forwards each one to the output
+/// given by its destination in the [RoutingTable]. Forwarding takes a fixed latency, and one element is routed per cycle.
+#[context_internal]
+pub struct NetworkContext<T: Clone + Routable> {
+    inputs: ReceiverGroup<T>,
+    outputs: Vec<Sender<T>>,
+    table: RoutingTable<T::Destination>,
+    latency: u64,
+}
+
+impl<T: DAMType + Routable> Context for NetworkContext<T> {
+    fn run_falliable(&mut self) -> anyhow::Result<()> {
+        while let Ok((_, ChannelElement { data, .. })) = self.inputs.select(&self.time) {
+            let destination = data.destination();
+            let port = self
+                .table
+                .lookup(&destination)
+                .ok_or_else(|| UtilityError::NoRoute(format!("{destination:?}")))?;
+            let output = self.outputs.get(port).ok_or(UtilityError::NoSuchPort {
+                port,
+                ports: self.outputs.len(),
+            })?;
+            output.enqueue(
+                &self.time,
+                ChannelElement::new(self.time.tick() + self.latency, data),
+            )?;
+            self.time.incr_cycles(1);
+        }
+        Ok(())
+    }
+}
+
+impl<T: DAMType + Routable> NetworkContext<T> {
+    /// Constructs a router over the given ports. Ties between inputs are broken round-robin.
+    pub fn new(
+        inputs: Vec<Receiver<T>>,
+        outputs: Vec<Sender<T>>,
+        table: RoutingTable<T::Destination>,
+        latency: u64,
+    ) -> Self {
+        let x = Self {
+            inputs: ReceiverGroup::new(inputs, TieBreak::RoundRobin),
+            outputs,
+            table,
+            latency,
+            context_info: Default::default(),
+        };
+        x.inputs.attach_receiver(&x);
+        x.outputs.iter().for_each(|output| output.attach_sender(&x));
+        x
+    }
+
+    /// A handle to the routing table, which may be used to update routes at runtime.
+    pub fn routing_table(&self) -> RoutingTable<T::Destination> {
+        self.table.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::{NetworkContext, Routable, RoutingTable};
+
+    use crate::{
+        channel::ChannelElement,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        types::StaticallySized,
+        utility_contexts::FunctionContext,
+    };
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    struct Packet {
+        src: (usize, usize),
+        dst: (usize, usize),
+    }
+
+    impl StaticallySized for Packet {
+        const SIZE: usize = 64;
+    }
+
+    impl Routable for Packet {
+        type Destination = (usize, usize);
+
+        fn destination(&self) -> Self::Destination {
+            self.dst
+        }
+    }
+
+    #[test]
+    fn test_routing_table_update() {
+        let table = RoutingTable::default();
+        let handle = table.clone();
+        assert_eq!(table.insert(3, 0), None);
+        assert_eq!(handle.insert(3, 1), Some(0));
+        assert_eq!(table.lookup(&3), Some(1));
+        assert_eq!(handle.remove(&3), Some(1));
+        assert_eq!(table.lookup(&3), None);
+    }
+
+    #[test]
+    fn test_mesh() {
+        // A 4x4 mesh with dimension-ordered (X, then Y) routing.
+        // Links only run east and south, so traffic always heads towards higher coordinates.
+        const DIM: usize = 4;
+        const PACKETS_PER_NODE: usize = 16;
+        const EJECT: usize = 0;
+        const EAST: usize = 1;
+
+        let mut parent = ProgramBuilder::default();
+        let mut inputs: Vec<Vec<_>> = (0..DIM * DIM).map(|_| vec![]).collect();
+        let mut outputs: Vec<Vec<_>> = (0..DIM * DIM).map(|_| vec![]).collect();
+        let index = |x: usize, y: usize| y * DIM + x;
+
+        let received = Arc::new(AtomicUsize::new(0));
+        let mut rng = fastrand::Rng::with_seed(0xda4);
+        for y in 0..DIM {
+            for x in 0..DIM {
+                let (inject_snd, inject_rcv) = parent.bounded(4);
+                inputs[index(x, y)].push(inject_rcv);
+                let packets: Vec<_> = (0..PACKETS_PER_NODE)
+                    .map(|_| Packet {
+                        src: (x, y),
+                        dst: (rng.usize(x..DIM), rng.usize(y..DIM)),
+                    })
+                    .collect();
+                let mut injector = FunctionContext::default();
+                inject_snd.attach_sender(&injector);
+                injector.set_run(move |time| {
+                    for packet in packets {
+                        inject_snd
+                            .enqueue(time, ChannelElement::new(time.tick(), packet))
+                            .unwrap();
+                        time.incr_cycles(1);
+                    }
+                });
+                parent.add_child(injector);
+
+                let (eject_snd, eject_rcv) = parent.bounded(4);
+                outputs[index(x, y)].push(eject_snd);
+                let mut sink = FunctionContext::default();
+                eject_rcv.attach_receiver(&sink);
+                let received = received.clone();
+                sink.set_run(move |time| {
+                    while let Ok(element) = eject_rcv.dequeue(time) {
+                        assert_eq!(element.data.dst, (x, y));
+                        assert!(element.data.src.0 <= x && element.data.src.1 <= y);
+                        received.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+                parent.add_child(sink);
+            }
+        }
+
+        for y in 0..DIM {
+            for x in 0..DIM {
+                if x + 1 < DIM {
+                    let (snd, rcv) = parent.bounded(2);
+                    outputs[index(x, y)].push(snd);
+                    inputs[index(x + 1, y)].push(rcv);
+                }
+                if y + 1 < DIM {
+                    let (snd, rcv) = parent.bounded(2);
+                    outputs[index(x, y)].push(snd);
+                    inputs[index(x, y + 1)].push(rcv);
+                }
+            }
+        }
+
+        for y in 0..DIM {
+            for x in 0..DIM {
+                // Ports are numbered in the order they were added: eject, then east (if present), then south.
+                let south = if x + 1 < DIM { EAST + 1 } else { EAST };
+                let table = (x..DIM)
+                    .flat_map(|dx| (y..DIM).map(move |dy| (dx, dy)))
+                    .map(|(dx, dy)| {
+                        let port = if dx > x {
+                            EAST
+                        } else if dy > y {
+                            south
+                        } else {
+                            EJECT
+                        };
+                        ((dx, dy), port)
+                    })
+                    .collect::<RoutingTable<_>>();
+                parent.add_child(NetworkContext::new(
+                    std::mem::take(&mut inputs[index(x, y)]),
+                    std::mem::take(&mut outputs[index(x, y)]),
+                    table,
+                    1,
+                ));
+            }
+        }
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+        assert_eq!(
+            received.load(Ordering::Relaxed),
+            DIM * DIM * PACKETS_PER_NODE
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+
+use dam_macros::context_internal;
+
+use crate::{
+    channel::{
+        utils::{EventTime, Peekable},
+        ChannelElement, Receiver, Sender,
+    },
+    types::DAMType,
+};
+
+use crate::context::Context;
+
+/// A single stage of a [PipelineContext].
+pub type PipelineStage<T> = Box<dyn Fn(T) -> T + Send + Sync>;
+
+/// Models a fully pipelined unit with a fixed number of stages, each of which takes `latency_per_stage` cycles.
+/// A new element may enter the pipeline every cycle, and leaves it `STAGES * latency_per_stage` cycles later,
+/// after being passed through each stage in order.
+/// The pipeline holds at most one element per cycle of latency, so when the output is full, the pipeline fills up and stops accepting input.
+#[context_internal]
+pub struct PipelineContext<T: Clone, const STAGES: usize> {
+    stages: [PipelineStage<T>; STAGES],
+    latency_per_stage: u64,
+    input: Receiver<T>,
+    output: Sender<T>,
+    // Timestamped with the cycle that they leave the final stage.
+    in_flight: VecDeque<ChannelElement<T>>,
+}
+
+impl<T: DAMType, const STAGES: usize> Context for PipelineContext<T, STAGES> {
+    fn run_falliable(&mut self) -> anyhow::Result<()> {
+        let total_latency = STAGES as u64 * self.latency_per_stage;
+        let depth = (total_latency as usize).max(1);
+        loop {
+            // At most one element leaves the final stage per cycle. A full output stalls the whole pipeline.
+            if self
+                .in_flight
+                .front()
+                .is_some_and(|head| head.time <= self.time.tick())
+            {
+                let ChannelElement { data, .. } = self.in_flight.pop_front().unwrap();
+                self.output
+                    .enqueue(&self.time, ChannelElement::new(self.time.tick(), data))?;
+            }
+
+            // A new element may enter the first stage as long as the pipeline has room for it.
+            let now = self.time.tick();
+            if self.in_flight.len() < depth {
+                if let EventTime::Ready(time) = self.input.next_event() {
+                    if time <= now {
+                        let ChannelElement { data, .. } = self.input.dequeue(&self.time)?;
+                        let result = self.stages.iter().fold(data, |value, stage| stage(value));
+                        self.in_flight
+                            .push_back(ChannelElement::new(now + total_latency, result));
+                    }
+                }
+            }
+
+            if !self.in_flight.is_empty() {
+                self.time.incr_cycles(1);
+                continue;
+            }
+
+            match self.input.next_event() {
+                EventTime::Ready(time) => self.time.advance(time),
+                EventTime::Nothing(time) => self.time.advance(time + 1),
+                EventTime::Closed => return Ok(()),
+            }
+        }
+    }
+}
+
+impl<T: DAMType, const STAGES: usize> PipelineContext<T, STAGES> {
+    /// Constructs a pipeline from its stages, which are applied in order.
+    pub fn new(
+        stages: [PipelineStage<T>; STAGES],
+        latency_per_stage: u64,
+        input: Receiver<T>,
+        output: Sender<T>,
+    ) -> Self {
+        let x = Self {
+            stages,
+            latency_per_stage,
+            input,
+            output,
+            in_flight: VecDeque::new(),
+            context_info: Default::default(),
+        };
+        x.input.attach_receiver(&x);
+        x.output.attach_sender(&x);
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PipelineContext, PipelineStage};
+
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::{CheckerContext, FunctionContext, GeneratorContext},
+    };
+
+    #[test]
+    fn test_pipeline_latency() {
+        let mut parent = ProgramBuilder::default();
+        let (in_send, in_recv) = parent.bounded(4);
+        let (out_send, out_recv) = parent.bounded(4);
+
+        let mut driver = FunctionContext::default();
+        in_send.attach_sender(&driver);
+        driver.set_run(move |time| {
+            in_send
+                .enqueue(time, ChannelElement::new(Time::new(0), 1u64))
+                .unwrap();
+        });
+        parent.add_child(driver);
+
+        let stages: [PipelineStage<u64>; 3] = [
+            Box::new(|x| x + 1),
+            Box::new(|x| x * 10),
+            Box::new(|x| x - 3),
+        ];
+        parent.add_child(PipelineContext::new(stages, 2, in_recv, out_send));
+
+        let mut checker = FunctionContext::default();
+        out_recv.attach_receiver(&checker);
+        checker.set_run(move |time| {
+            let result = out_recv.dequeue(time).unwrap();
+            // The input reaches the pipeline at tick 1, and the result crosses the output channel one tick after leaving the final stage.
+            assert_eq!(result.time, Time::new(1 + 6 + 1));
+            assert_eq!(result.data, 17);
+            assert!(out_recv.dequeue(time).is_err());
+        });
+        parent.add_child(checker);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_pipeline_throughput() {
+        let mut parent = ProgramBuilder::default();
+        let (in_send, in_recv) = parent.bounded(8);
+        let (out_send, out_recv) = parent.bounded(8);
+
+        let generator = GeneratorContext::new(|| 0..32u64, in_send);
+        parent.add_child(generator);
+        let stages: [PipelineStage<u64>; 2] = [Box::new(|x| x * 2), Box::new(|x| x + 1)];
+        parent.add_child(PipelineContext::new(stages, 3, in_recv, out_send));
+        parent.add_child(CheckerContext::new(
+            || (0..32u64).map(|x| x * 2 + 1),
+            out_recv,
+        ));
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_pipeline_backpressure() {
+        const NUM_ELEMENTS: u64 = 16;
+        // Two stages of two cycles each hold up to four elements.
+        const DEPTH: u64 = 4;
+        const SINK_PERIOD: u64 = 10;
+        let mut parent = ProgramBuilder::default();
+        let (in_send, in_recv) = parent.bounded(1);
+        let (out_send, out_recv) = parent.bounded(1);
+
+        let mut source = FunctionContext::default();
+        in_send.attach_sender(&source);
+        source.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                in_send
+                    .enqueue(time, ChannelElement::new(Time::new(0), iter))
+                    .unwrap();
+            }
+            // Besides the pipeline itself, only the two channels and the element leaving the final stage can hold elements,
+            // so the final sends had to wait for the sink to make room.
+            let buffered = DEPTH + 3;
+            assert!(time.tick() >= Time::new((NUM_ELEMENTS - buffered - 1) * SINK_PERIOD));
+        });
+        parent.add_child(source);
+
+        let stages: [PipelineStage<u64>; 2] = [Box::new(|x| x * 2), Box::new(|x| x + 1)];
+        parent.add_child(PipelineContext::new(stages, 2, in_recv, out_send));
+
+        let mut sink = FunctionContext::default();
+        out_recv.attach_receiver(&sink);
+        sink.set_run(move |time| {
+            let mut last = Time::new(0);
+            for iter in 0..NUM_ELEMENTS {
+                let element = out_recv.dequeue(time).unwrap();
+                assert_eq!(element.data, iter * 2 + 1);
+                assert!(element.time >= last);
+                last = element.time;
+                time.incr_cycles(SINK_PERIOD);
+            }
+            assert!(out_recv.dequeue(time).is_err());
+        });
+        parent.add_child(sink);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
@@ -0,0 +1,152 @@
+use dam_macros::context_internal;
+
+use crate::{
+    channel::{
+        group::{ReceiverGroup, TieBreak},
+        ChannelElement, Receiver, Sender,
+    },
+    datastructures::Time,
+    types::DAMType,
+};
+
+use crate::context::Context;
+
+use super::UtilityError;
+
+/// Models a reduction network, such as an adder tree, which combines one element from each of its inputs into a single output.
+/// Elements are folded into the accumulator in the order that they arrive. Once every input has contributed to a round,
+/// the accumulator is sent `latency` cycles after the latest element of the round, and the next round starts from the initial value.
+/// An input which runs ahead of the others is not read again until the next round, so it is held back by its channel.
+#[context_internal]
+pub struct ReduceContext<T: Clone, Acc: Clone, FType>
+where
+    FType: Fn(Acc, T) -> Acc + Send + Sync,
+{
+    inputs: ReceiverGroup<T>,
+    output: Sender<Acc>,
+    initial: Acc,
+    reduce: FType,
+    latency: u64,
+}
+
+impl<T: DAMType, Acc: DAMType, FType> ReduceContext<T, Acc, FType>
+where
+    FType: Fn(Acc, T) -> Acc + Send + Sync,
+{
+    /// Constructs a ReduceContext from its inputs, its output, the initial accumulator for each round, the reduction function, and the reduction latency.
+    pub fn new(
+        inputs: Vec<Receiver<T>>,
+        output: Sender<Acc>,
+        initial: Acc,
+        reduce: FType,
+        latency: u64,
+    ) -> Self {
+        assert!(!inputs.is_empty(), "A reduction needs at least one input");
+        let x = Self {
+            inputs: ReceiverGroup::new(inputs, TieBreak::Lowest),
+            output,
+            initial,
+            reduce,
+            latency,
+            context_info: Default::default(),
+        };
+        x.inputs.attach_receiver(&x);
+        x.output.attach_sender(&x);
+        x
+    }
+}
+
+impl<T: DAMType, Acc: DAMType, FType> Context for ReduceContext<T, Acc, FType>
+where
+    FType: Fn(Acc, T) -> Acc + Send + Sync,
+{
+    fn run_falliable(&mut self) -> anyhow::Result<()> {
+        for round in 0.. {
+            let mut acc = self.initial.clone();
+            let mut latest = Time::new(0);
+            let mut contributed = vec![false; self.inputs.len()];
+            while let Some(missing) = contributed.iter().position(|done| !done) {
+                match self.inputs.select_from(&self.time, |ind| !contributed[ind]) {
+                    Ok((ind, ChannelElement { time, data })) => {
+                        acc = (self.reduce)(acc, data);
+                        latest = latest.max(time);
+                        contributed[ind] = true;
+                    }
+                    // Every input closed cleanly between rounds.
+                    Err(_) if contributed.iter().all(|done| !done) => return Ok(()),
+                    Err(_) => Err(UtilityError::Receiver {
+                        iteration: round,
+                        channel: self.inputs.receivers()[missing].id(),
+                    })?,
+                }
+            }
+
+            self.output
+                .enqueue(&self.time, ChannelElement::new(latest + self.latency, acc))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReduceContext;
+
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+    };
+
+    #[test]
+    fn test_adder_tree() {
+        const INPUTS: u64 = 4;
+        const ROUNDS: u64 = 32;
+        const LATENCY: u64 = 3;
+        let mut parent = ProgramBuilder::default();
+        let mut inputs = vec![];
+        for input in 0..INPUTS {
+            let (snd, rcv) = parent.bounded(4);
+            inputs.push(rcv);
+            let mut source = FunctionContext::default();
+            snd.attach_sender(&source);
+            source.set_run(move |time| {
+                // Lower inputs run ahead of the higher ones.
+                for round in 0..ROUNDS {
+                    let element =
+                        ChannelElement::new(Time::new(round * (input + 1) + 1), round + input);
+                    snd.enqueue(time, element).unwrap();
+                }
+            });
+            parent.add_child(source);
+        }
+
+        let (out_snd, out_rcv) = parent.bounded(4);
+        parent.add_child(ReduceContext::new(
+            inputs,
+            out_snd,
+            0u64,
+            |acc, x| acc + x,
+            LATENCY,
+        ));
+
+        let mut checker = FunctionContext::default();
+        out_rcv.attach_receiver(&checker);
+        checker.set_run(move |time| {
+            for round in 0..ROUNDS {
+                let element = out_rcv.dequeue(time).unwrap();
+                assert_eq!(element.data, round * INPUTS + (0..INPUTS).sum::<u64>());
+                assert_eq!(element.time, Time::new(round * INPUTS + 1 + LATENCY));
+            }
+            assert!(out_rcv.dequeue(time).is_err());
+        });
+        parent.add_child(checker);
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
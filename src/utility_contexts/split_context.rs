@@ -0,0 +1,115 @@
+use dam_macros::context_internal;
+
+use crate::context_tools::*;
+
+use crate::{channel::Either, context::Context};
+
+/// A context which splits a single input stream into two outputs, such as a parser separating control from data.
+/// Each input element is passed through the split function, and the result is sent to the corresponding output `latency` cycles after the input arrived.
+#[context_internal]
+pub struct SplitContext<T: Clone, U: Clone, V: Clone, FType>
+where
+    FType: Fn(T) -> Either<U, V> + Send + Sync,
+{
+    input: Receiver<T>,
+    left: Sender<U>,
+    right: Sender<V>,
+    split: FType,
+    latency: u64,
+}
+
+impl<T: DAMType, U: DAMType, V: DAMType, FType> Context for SplitContext<T, U, V, FType>
+where
+    FType: Fn(T) -> Either<U, V> + Send + Sync,
+{
+    fn run_falliable(&mut self) -> anyhow::Result<()> {
+        while let Ok(ChannelElement { data, .. }) = self.input.dequeue(&self.time) {
+            let time = self.time.tick() + self.latency;
+            match (self.split)(data) {
+                Either::Left(left) => self
+                    .left
+                    .enqueue(&self.time, ChannelElement::new(time, left))?,
+                Either::Right(right) => self
+                    .right
+                    .enqueue(&self.time, ChannelElement::new(time, right))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: DAMType, U: DAMType, V: DAMType, FType> SplitContext<T, U, V, FType>
+where
+    FType: Fn(T) -> Either<U, V> + Send + Sync,
+{
+    /// Constructs a SplitContext from its input, the split function, its two outputs, and the processing latency.
+    pub fn new(
+        input: Receiver<T>,
+        split: FType,
+        left: Sender<U>,
+        right: Sender<V>,
+        latency: u64,
+    ) -> Self {
+        let x = Self {
+            input,
+            left,
+            right,
+            split,
+            latency,
+            context_info: Default::default(),
+        };
+        x.input.attach_receiver(&x);
+        x.left.attach_sender(&x);
+        x.right.attach_sender(&x);
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Either, SplitContext};
+
+    use crate::{
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::{CheckerContext, GeneratorContext},
+    };
+
+    #[test]
+    fn test_alternating_split() {
+        const NUM_ELEMENTS: u64 = 32;
+        let mut parent = ProgramBuilder::default();
+        let (in_send, in_recv) = parent.bounded(4);
+        let (left_send, left_recv) = parent.bounded(4);
+        let (right_send, right_recv) = parent.bounded(4);
+
+        parent.add_child(GeneratorContext::new(|| 0..NUM_ELEMENTS, in_send));
+        parent.add_child(SplitContext::new(
+            in_recv,
+            |x: u64| {
+                if x % 2 == 0 {
+                    Either::Left(x / 2)
+                } else {
+                    Either::Right(-(x as i32))
+                }
+            },
+            left_send,
+            right_send,
+            2,
+        ));
+        parent.add_child(CheckerContext::new(|| 0..NUM_ELEMENTS / 2, left_recv));
+        parent.add_child(CheckerContext::new(
+            || {
+                (0..NUM_ELEMENTS)
+                    .filter(|x| x % 2 == 1)
+                    .map(|x| -(x as i32))
+            },
+            right_recv,
+        ));
+
+        let executed = parent
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
@@ -1,8 +1,12 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
 use dam_macros::event_type_internal;
 use linkme::distributed_slice;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::{
     datastructures::*,
@@ -32,6 +36,15 @@ impl TimeManager {
         }
     }
 
+    /// Constructs an owned time which starts at `start` instead of zero, such as for a context resuming from a checkpoint.
+    /// Times remain absolute, so elements timestamped before `start` are simply observed at `start`.
+    /// This must be installed before the context is attached to any channels, as they capture a view of the time.
+    pub fn with_epoch(start: Time) -> TimeManager {
+        let manager = Self::new();
+        manager.underlying.time.try_advance(start);
+        manager
+    }
+
     /// Constructs a [super::BasicContextView] of the owned time.
     pub fn view(&self) -> BasicContextView {
         BasicContextView {
@@ -84,6 +97,45 @@ impl TimeManager {
         self.underlying.time.load_relaxed()
     }
 
+    /// Bounds the context to `max_ticks`: once its time exceeds this, channel operations through this manager report that the channel is closed.
+    pub fn set_tick_budget(&self, max_ticks: u64) {
+        self.underlying.set_tick_budget(max_ticks);
+    }
+
+    /// Whether the context has run past its tick budget, if one was set.
+    #[inline(always)]
+    pub fn budget_exhausted(&self) -> bool {
+        let limit = self.underlying.budget_limit.load(Ordering::Relaxed);
+        limit != 0 && self.tick() >= Time::new(limit)
+    }
+
+    /// Captures the current time and tick budget, such as for checkpointing a context.
+    pub fn snapshot(&self) -> TimeManagerSnapshot {
+        let limit = self.underlying.budget_limit.load(Ordering::Relaxed);
+        TimeManagerSnapshot {
+            tick: self.underlying.time.load(),
+            tick_budget: limit.checked_sub(1),
+        }
+    }
+
+    /// Reinstates a snapshot taken with [TimeManager::snapshot], waking any views which were waiting on the restored time.
+    /// As other contexts may already have observed the current time, time can only move forward: restoring a snapshot from the past returns a [RestoreError] and leaves the manager untouched.
+    pub fn restore(&self, snapshot: &TimeManagerSnapshot) -> Result<(), RestoreError> {
+        let current = self.underlying.time.load();
+        if snapshot.tick < current {
+            return Err(RestoreError {
+                snapshot: snapshot.tick,
+                current,
+            });
+        }
+        self.underlying.budget_limit.store(
+            snapshot.tick_budget.map_or(0, |max| max.saturating_add(1)),
+            Ordering::Relaxed,
+        );
+        self.advance(snapshot.tick);
+        Ok(())
+    }
+
     /// Explicitly advances the context to infinite time.
     /// This is useful if we don't want to wait for `Drop` to trigger.
     pub fn cleanup(&mut self) {
@@ -93,6 +145,27 @@ impl TimeManager {
     }
 }
 
+/// The state of a [TimeManager], see [TimeManager::snapshot].
+/// A snapshot can only be restored into a manager which has not yet passed its tick, so it cannot rewind a context that is already running.
+/// Only time is captured: channel contents and the state of the context itself must be checkpointed separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeManagerSnapshot {
+    /// The context's time
+    pub tick: Time,
+    /// The tick budget, if one was set
+    pub tick_budget: Option<u64>,
+}
+
+/// The snapshot passed to [TimeManager::restore] was from before the manager's current time.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("Cannot restore time {snapshot:?} as it is before the current time {current:?}")]
+pub struct RestoreError {
+    /// The time of the snapshot
+    pub snapshot: Time,
+    /// The manager's current time
+    pub current: Time,
+}
+
 /// A simple view of a "primitive" context's time.
 #[derive(Clone)]
 pub struct BasicContextView {
@@ -153,11 +226,45 @@ impl ContextView for BasicContextView {
         }
     }
 
+    fn try_wait_until(&self, when: Time, timeout: std::time::Duration) -> Option<Time> {
+        let cur_time = self.under.time.load();
+        if cur_time >= when {
+            return Some(cur_time);
+        }
+
+        let deadline = std::time::Instant::now() + timeout;
+        // Register a signal, so that we are woken up early if the time is reached.
+        self.under.signal_buffer.lock().push(SignalElement {
+            when,
+            thread: crate::shim::current(),
+        });
+        loop {
+            // Park is Acquire, so the load can be relaxed
+            let cur_time = self.under.time.load_relaxed();
+            if cur_time >= when {
+                return Some(self.under.time.load());
+            }
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            if remaining.is_zero() {
+                // The stale signal is harmless, as parking always re-checks the time.
+                return None;
+            }
+            crate::shim::park_timeout(remaining);
+        }
+    }
+
     fn tick_lower_bound(&self) -> Time {
         self.under.time.load()
     }
 }
 
+impl BasicContextView {
+    /// See [TimeManager::set_tick_budget]. This allows a budget to be installed after the context has been constructed.
+    pub(crate) fn set_tick_budget(&self, max_ticks: u64) {
+        self.under.set_tick_budget(max_ticks);
+    }
+}
+
 /// Registers a waking callback to a TimeManager.
 /// This is used to implement wait_until on [BasicContextView]s
 #[derive(Debug, Clone)]
@@ -171,4 +278,138 @@ struct SignalElement {
 struct TimeInfo {
     time: crossbeam::utils::CachePadded<AtomicTime>,
     signal_buffer: crossbeam::utils::CachePadded<parking_lot::Mutex<Vec<SignalElement>>>,
+
+    // The first tick past the budget, or 0 if there is no budget.
+    budget_limit: AtomicU64,
+}
+
+impl TimeInfo {
+    fn set_tick_budget(&self, max_ticks: u64) {
+        self.budget_limit
+            .store(max_ticks.saturating_add(1), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{RestoreError, TimeManager, TimeManagerSnapshot};
+    use crate::{
+        channel::ChannelElement,
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+        view::{ContextView, TimeViewable},
+    };
+
+    #[test]
+    fn test_with_epoch() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(2);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 1..=4u64 {
+                snd.enqueue(time, ChannelElement::new(Time::new(iter), iter))
+                    .unwrap();
+            }
+            // The receiver only acknowledged elements once it was past its epoch.
+            assert!(time.tick() > Time::new(1000));
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        receiver.time = TimeManager::with_epoch(Time::new(1000));
+        assert_eq!(receiver.view().tick_lower_bound(), Time::new(1000));
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            time.advance(Time::new(5));
+            assert_eq!(time.tick(), Time::new(1000));
+            for iter in 1..=4u64 {
+                let element = rcv.dequeue(time).unwrap();
+                assert_eq!(element.data, iter);
+                assert!(element.time < Time::new(1000));
+                assert_eq!(time.tick(), Time::new(1000));
+            }
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let original = TimeManager::new();
+        original.set_tick_budget(100);
+        original.incr_cycles(42);
+        let snapshot = original.snapshot();
+        assert_eq!(snapshot.tick, Time::new(42));
+        assert_eq!(snapshot.tick_budget, Some(100));
+
+        let serialized = serde_json::to_string(&snapshot).unwrap();
+        let deserialized: TimeManagerSnapshot = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, snapshot);
+
+        let restored = TimeManager::new();
+        let view = restored.view();
+        restored.restore(&deserialized).unwrap();
+        assert_eq!(view.tick_lower_bound(), Time::new(42));
+        assert_eq!(restored.snapshot(), snapshot);
+        restored.incr_cycles(59);
+        assert!(restored.budget_exhausted());
+
+        let unbounded = TimeManager::new().snapshot();
+        assert_eq!(unbounded.tick_budget, None);
+    }
+
+    #[test]
+    fn test_restore_past() {
+        let manager = TimeManager::new();
+        let snapshot = manager.snapshot();
+        manager.incr_cycles(1);
+        assert_eq!(
+            manager.restore(&snapshot),
+            Err(RestoreError {
+                snapshot: Time::new(0),
+                current: Time::new(1),
+            })
+        );
+        assert_eq!(manager.tick(), Time::new(1));
+    }
+
+    #[test]
+    fn test_try_wait_until() {
+        let mut ctx = ProgramBuilder::default();
+
+        let mut slow = FunctionContext::default();
+        slow.set_run(|time| {
+            crate::shim::sleep(Duration::from_millis(200));
+            time.incr_cycles(5);
+        });
+        let view = slow.view();
+        ctx.add_child(slow);
+
+        let mut waiter = FunctionContext::default();
+        waiter.set_run(move |_| {
+            assert_eq!(
+                view.try_wait_until(Time::new(3), Duration::from_millis(10)),
+                None
+            );
+            let reached = view.try_wait_until(Time::new(3), Duration::from_secs(30));
+            assert!(reached.unwrap() >= Time::new(3));
+        });
+        ctx.add_child(waiter);
+
+        let executed = ctx
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
 }
@@ -0,0 +1,64 @@
+use super::TimeManager;
+
+/// A clock domain layered on top of a context's [TimeManager].
+/// Each cycle of the clock spans `ticks_per_cycle` ticks of the underlying time, so contexts using clocks with
+/// different periods still share the same base tick while running at different rates.
+#[derive(Debug, Clone, Copy)]
+pub struct SimulationClock<'a> {
+    manager: &'a TimeManager,
+    ticks_per_cycle: u64,
+}
+
+impl<'a> SimulationClock<'a> {
+    /// Constructs a clock over the given manager, with a period of `ticks_per_cycle` ticks.
+    pub fn new(manager: &'a TimeManager, ticks_per_cycle: u64) -> Self {
+        assert!(ticks_per_cycle > 0, "A clock must have a non-zero period");
+        Self {
+            manager,
+            ticks_per_cycle,
+        }
+    }
+
+    /// The period of the clock, in ticks.
+    pub fn ticks_per_cycle(&self) -> u64 {
+        self.ticks_per_cycle
+    }
+
+    /// Advances the underlying manager by a single cycle of this clock.
+    pub fn tick(&self) {
+        self.manager.incr_cycles(self.ticks_per_cycle);
+    }
+
+    /// The number of complete cycles of this clock which have elapsed.
+    pub fn current_cycle(&self) -> u64 {
+        self.manager.tick().time() / self.ticks_per_cycle
+    }
+
+    /// Advances the underlying manager by `n` cycles of this clock.
+    pub fn wait_cycles(&self, n: u64) {
+        self.manager.incr_cycles(n * self.ticks_per_cycle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SimulationClock;
+    use crate::view::TimeManager;
+
+    #[test]
+    fn test_clock_rates() {
+        let manager = TimeManager::new();
+        let fast = SimulationClock::new(&manager, 2);
+        let slow = SimulationClock::new(&manager, 5);
+
+        fast.tick();
+        assert_eq!(manager.tick().time(), 2);
+        assert_eq!(fast.current_cycle(), 1);
+        assert_eq!(slow.current_cycle(), 0);
+
+        slow.wait_cycles(2);
+        assert_eq!(manager.tick().time(), 12);
+        assert_eq!(fast.current_cycle(), 6);
+        assert_eq!(slow.current_cycle(), 2);
+    }
+}
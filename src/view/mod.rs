@@ -1,8 +1,12 @@
 mod basic;
+mod clock;
 mod parent;
 
 pub use basic::BasicContextView;
+pub use basic::RestoreError;
 pub use basic::TimeManager;
+pub use basic::TimeManagerSnapshot;
+pub use clock::SimulationClock;
 pub use parent::ParentView;
 
 use crate::datastructures::Time;
@@ -14,11 +18,40 @@ pub trait ContextView {
     /// This may or may not actually block, depending on whether the viewed context has already progressed.
     fn wait_until(&self, when: Time) -> Time;
 
+    /// Like [ContextView::wait_until], but gives up after `timeout` of wall-clock time.
+    /// Returns the time of the viewed context if it reached `when`, or None on timeout.
+    /// The default implementation polls [ContextView::tick_lower_bound], yielding between checks.
+    fn try_wait_until(&self, when: Time, timeout: std::time::Duration) -> Option<Time> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let cur_time = self.tick_lower_bound();
+            if cur_time >= when {
+                return Some(cur_time);
+            }
+            if std::time::Instant::now() >= deadline {
+                return None;
+            }
+            crate::shim::yield_now();
+        }
+    }
+
     /// Reads the time of the viewed context.
     /// This is only guaranteed to be a lower bound, as the viewed context may have progressed since the write.
     fn tick_lower_bound(&self) -> Time;
 }
 
+/// How long channel operations may wait on the context at the other end of the channel, in wall-clock time.
+/// This applies to peeking a receiver and waiting for capacity on a sender, see [crate::simulation::InitializationOptions].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeoutPolicy {
+    /// Wait as long as it takes
+    #[default]
+    Unbounded,
+
+    /// Panic if the other end does not catch up within the duration, which usually indicates a deadlock.
+    PanicAfter(std::time::Duration),
+}
+
 /// enum_delegate enum to change users from `Box<dyn ContextView>`.
 #[enum_delegate::implement(ContextView)]
 #[derive(Clone)]
@@ -19,6 +19,17 @@ impl ContextView for ParentView {
         individual_signals.into_iter().min().unwrap_or(when)
     }
 
+    fn try_wait_until(&self, when: Time, timeout: std::time::Duration) -> Option<Time> {
+        let deadline = std::time::Instant::now() + timeout;
+        let mut min_time: Option<Time> = None;
+        for child in &self.child_views {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let child_time = child.try_wait_until(when, remaining)?;
+            min_time = Some(min_time.map_or(child_time, |time| time.min(child_time)));
+        }
+        Some(min_time.unwrap_or(when))
+    }
+
     fn tick_lower_bound(&self) -> Time {
         let min_time = self
             .child_views
@@ -31,3 +42,43 @@ impl ContextView for ParentView {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ParentView;
+    use crate::{
+        datastructures::Time,
+        simulation::{InitializationOptions, ProgramBuilder, RunOptions},
+        utility_contexts::FunctionContext,
+        view::{ContextView, TimeViewable},
+    };
+
+    #[test]
+    fn test_try_wait_until_takes_child_minimum() {
+        let mut ctx = ProgramBuilder::default();
+        let mut child_views = vec![];
+        for cycles in [5, 7] {
+            let mut child = FunctionContext::default();
+            child.set_run(move |time| time.incr_cycles(cycles));
+            child_views.push(child.view());
+            ctx.add_child(child);
+        }
+        let parent = ParentView { child_views };
+
+        let mut waiter = FunctionContext::default();
+        waiter.set_run(move |_| {
+            // The children jump straight past the requested time, so the result should reflect where they actually are.
+            let reached = parent.try_wait_until(Time::new(3), Duration::from_secs(30));
+            assert!(reached.unwrap() >= Time::new(5));
+        });
+        ctx.add_child(waiter);
+
+        let executed = ctx
+            .initialize(InitializationOptions::default())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+}
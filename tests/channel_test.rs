@@ -1,7 +1,21 @@
 #[cfg(test)]
 mod tests {
 
-    use dam::{channel::ChannelElement, simulation::*, utility_contexts::FunctionContext};
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use dam::{
+        channel::{
+            adapters::{RecvAdapter, SendAdapter},
+            ChannelElement, ChannelEvent, ChannelID, CloseError, PeekResult, SendOptions,
+        },
+        context::Context,
+        simulation::*,
+        structures::{Identifiable, Time},
+        utility_contexts::FunctionContext,
+    };
 
     // The tests will take TEST_SIZE * MAX_MS_SLEEP / 2 on average.
     const TEST_SIZE: i32 = 1 << 8;
@@ -27,6 +41,1805 @@ mod tests {
         run_channel_test(TEST_SIZE, true, None);
     }
 
+    #[test]
+    fn test_close_gracefully_noinfer() {
+        run_close_gracefully_test(false, 0, Ok(()));
+    }
+
+    #[test]
+    fn test_close_gracefully_infer() {
+        run_close_gracefully_test(true, 0, Ok(()));
+    }
+
+    #[test]
+    fn test_close_gracefully_timeout_noinfer() {
+        run_close_gracefully_test(false, 100, Err(()));
+    }
+
+    #[test]
+    fn test_close_gracefully_timeout_infer() {
+        run_close_gracefully_test(true, 100, Err(()));
+    }
+
+    fn run_close_gracefully_test(
+        flavor_inference: bool,
+        receiver_delay: u64,
+        expected: Result<(), ()>,
+    ) {
+        const NUM_ELEMENTS: i32 = 4;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(8);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                time.incr_cycles(1);
+            }
+            match (snd.close_gracefully(time, 10), expected) {
+                (Ok(()), Ok(())) => {}
+                (Err(CloseError::DrainTimeout), Err(())) => {}
+                (result, _) => panic!("Unexpected result from close_gracefully: {result:?}"),
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            time.incr_cycles(receiver_delay);
+            for iter in 0..NUM_ELEMENTS {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+                time.incr_cycles(1);
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(
+                InitializationOptionsBuilder::default()
+                    .run_flavor_inference(flavor_inference)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    fn run_send_after_drain_timeout_test(flavor_inference: bool) {
+        const CAPACITY: usize = 4;
+        const RECEIVER_DELAY: u64 = 100;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(CAPACITY);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..CAPACITY {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+            }
+            assert!(matches!(
+                snd.close_gracefully(time, 10),
+                Err(CloseError::DrainTimeout)
+            ));
+            // The channel is still full, so this waits for the receiver to free up a slot.
+            snd.enqueue(time, ChannelElement::new(time.tick() + 1, CAPACITY))
+                .unwrap();
+            assert!(time.tick() > Time::new(RECEIVER_DELAY));
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            time.incr_cycles(RECEIVER_DELAY);
+            for iter in 0..=CAPACITY {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(
+                InitializationOptionsBuilder::default()
+                    .run_flavor_inference(flavor_inference)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_send_after_drain_timeout() {
+        run_send_after_drain_timeout_test(false);
+        run_send_after_drain_timeout_test(true);
+    }
+
+    #[test]
+    fn test_advance_n() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..10 {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                time.incr_cycles(1);
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            rcv.advance_head(time).unwrap();
+            assert_eq!(rcv.dequeue(time).unwrap().data, 1);
+            rcv.advance_n(3, time).unwrap();
+            assert_eq!(rcv.dequeue(time).unwrap().data, 5);
+            // Only four elements remain, so skipping five should observe the close.
+            assert!(rcv.advance_n(5, time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_enqueue_or_void() {
+        let mut ctx = ProgramBuilder::default();
+        let void = ctx.void();
+        let (snd, rcv) = ctx.bounded(1);
+
+        let mut sender = FunctionContext::default();
+        void.attach_sender(&sender);
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            assert!(void.is_void());
+            assert!(!snd.is_void());
+            for iter in 0..4 {
+                void.enqueue_or_void(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                snd.enqueue_or_void(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 0..4 {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_first_available_time() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..3u64 {
+                snd.enqueue(time, ChannelElement::new(Time::new(10 * (iter + 1)), iter))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            assert_eq!(rcv.peek_next(time).unwrap().data, 0);
+            assert_eq!(rcv.first_available_time(), Some(Time::new(10)));
+            // Looking ahead should neither consume the element nor move time.
+            assert_eq!(rcv.first_available_time(), Some(Time::new(10)));
+            assert!(rcv.has_pending());
+            assert_eq!(time.tick(), Time::new(10));
+
+            for iter in 0..3 {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+            }
+            assert!(rcv.dequeue(time).is_err());
+            assert_eq!(rcv.first_available_time(), None);
+            assert!(!rcv.has_pending());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_element_ordering() {
+        let early = ChannelElement::new(Time::new(3), 7);
+        let late = ChannelElement::new(Time::new(5), 1);
+        let tied = ChannelElement::new(Time::new(3), 2);
+
+        let mut heap: std::collections::BinaryHeap<_> = [early.clone(), late.clone(), tied.clone()]
+            .into_iter()
+            .map(std::cmp::Reverse)
+            .collect();
+        let order: Vec<_> = std::iter::from_fn(|| heap.pop().map(|elem| elem.0.data)).collect();
+        assert_eq!(order, vec![2, 7, 1]);
+
+        assert_eq!(ChannelElement::min_time(late.clone(), early.clone()), early);
+        assert_eq!(ChannelElement::max_time(early.clone(), late.clone()), late);
+        // Ties keep the first argument, regardless of the data.
+        assert_eq!(ChannelElement::min_time(early.clone(), tied.clone()), early);
+        assert_eq!(ChannelElement::max_time(tied.clone(), early), tied);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "(pe0_output): Calling remaining_capacity on an uninitialized sender"
+    )]
+    fn test_debug_label() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded::<u32>(4);
+        assert!(rcv.debug_label().is_none());
+        snd.set_debug_label("pe0_output");
+        assert_eq!(rcv.debug_label().as_deref(), Some("pe0_output"));
+
+        // The channel hasn't been initialized, so this panics.
+        snd.peek_remaining_capacity();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "(late_label): Attempting to query the capacity of a terminated sender"
+    )]
+    fn test_terminated_debug_label() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, _rcv) = ctx.bounded::<u32>(4);
+        snd.close();
+        // Labels set after the sender terminated still show up in its panics.
+        snd.set_debug_label("late_label");
+        snd.peek_remaining_capacity();
+    }
+
+    fn run_remaining_capacity_test(flavor_inference: bool) {
+        const CAPACITY: usize = 4;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(CAPACITY);
+        let (done_snd, done_rcv) = ctx.bounded(1);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        done_rcv.attach_receiver(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..CAPACITY {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                assert_eq!(snd.peek_remaining_capacity(), CAPACITY - iter - 1);
+            }
+            // Once the receiver has drained the channel, all of the capacity is available again.
+            done_rcv.dequeue(time).unwrap();
+            assert_eq!(snd.peek_remaining_capacity(), CAPACITY);
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        done_snd.attach_sender(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 0..CAPACITY {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+            }
+            done_snd
+                .enqueue(time, ChannelElement::new(time.tick() + 1, true))
+                .unwrap();
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(
+                InitializationOptionsBuilder::default()
+                    .run_flavor_inference(flavor_inference)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_remaining_capacity_recovers() {
+        // Without inference the channel is acyclic, and with it the feedback through `done` makes it cyclic.
+        run_remaining_capacity_test(false);
+        run_remaining_capacity_test(true);
+    }
+
+    #[test]
+    fn test_channel_names() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, _rcv) = ctx.bounded::<u32>(4);
+        let (other, _other_rcv) = ctx.bounded::<u32>(4);
+        assert!(snd.id().name().is_none());
+
+        snd.set_debug_label("test_channel_names::data");
+        other.set_debug_label("test_channel_names::control");
+        assert_eq!(
+            ChannelID::from_name("test_channel_names::data"),
+            Some(snd.id())
+        );
+        assert_eq!(
+            other.id().name().as_deref(),
+            Some("test_channel_names::control")
+        );
+
+        // Relabelling a channel frees up its old name.
+        snd.set_debug_label("test_channel_names::renamed");
+        assert!(ChannelID::from_name("test_channel_names::data").is_none());
+        assert_eq!(
+            ChannelID::from_name("test_channel_names::renamed"),
+            Some(snd.id())
+        );
+        assert!(ChannelID::from_name("test_channel_names::missing").is_none());
+
+        // Dropping the program releases its names.
+        drop((ctx, snd, _rcv, other, _other_rcv));
+        assert!(ChannelID::from_name("test_channel_names::renamed").is_none());
+        assert!(ChannelID::from_name("test_channel_names::control").is_none());
+    }
+
+    #[test]
+    fn test_shared_channel_names() {
+        const SHARED: &str = "test_shared_channel_names::shared";
+        let mut ctx = ProgramBuilder::default();
+        let (first, _first_rcv) = ctx.bounded::<u32>(4);
+        let (second, _second_rcv) = ctx.bounded::<u32>(4);
+
+        first.set_debug_label(SHARED);
+        second.set_debug_label(SHARED);
+        assert_eq!(ChannelID::from_name(SHARED), Some(second.id()));
+
+        // Releasing the most recent holder falls back to the other channel with the label.
+        second.set_debug_label("test_shared_channel_names::other");
+        assert_eq!(ChannelID::from_name(SHARED), Some(first.id()));
+        second.set_debug_label(SHARED);
+        assert_eq!(ChannelID::from_name(SHARED), Some(second.id()));
+
+        drop((ctx, second, _second_rcv));
+        assert_eq!(ChannelID::from_name(SHARED), Some(first.id()));
+        first.set_debug_label("test_shared_channel_names::renamed");
+        assert!(ChannelID::from_name(SHARED).is_none());
+    }
+
+    #[test]
+    fn test_annotate_with_context() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+        let mut sender = FunctionContext::default();
+        let mut receiver = FunctionContext::default();
+        sender.annotate_sender(&snd);
+        assert_eq!(
+            snd.debug_label().as_deref(),
+            Some(format!("FunctionContext({})", sender.id()).as_str())
+        );
+        rcv.annotate_with_context(&receiver);
+        assert_eq!(
+            snd.debug_label().as_deref(),
+            Some(format!("FunctionContext({})", receiver.id()).as_str())
+        );
+
+        sender.set_run(move |time| {
+            snd.enqueue(time, ChannelElement::new(time.tick(), 1u32))
+                .unwrap()
+        });
+        receiver.set_run(move |time| assert_eq!(rcv.dequeue(time).unwrap().data, 1));
+        ctx.add_child(sender);
+        ctx.add_child(receiver);
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_sender_receiver_debug() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(8);
+        snd.set_debug_label("test_sender_receiver_debug");
+        assert!(format!("{snd:?}").contains("uninitialized"));
+        let display = snd.to_string();
+        assert!(display.starts_with("Sender(Channel "), "{display}");
+        assert!(
+            display.ends_with("(test_sender_receiver_debug))"),
+            "{display}"
+        );
+
+        let (done_snd, done_rcv) = ctx.bounded(1);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        done_rcv.attach_receiver(&sender);
+        sender.set_run(move |time| {
+            snd.enqueue(time, ChannelElement::new(time.tick() + 1, 0u32))
+                .unwrap();
+            snd.enqueue(time, ChannelElement::new(time.tick() + 1, 1u32))
+                .unwrap();
+            let debug = format!("{snd:?}");
+            assert!(debug.contains("occupancy: 2/8"), "{debug}");
+            assert!(debug.contains("test_sender_receiver_debug"), "{debug}");
+
+            for iter in 2..8u32 {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+            }
+            let debug = format!("{snd:?}");
+            assert!(debug.contains("occupancy: 8/8"), "{debug}");
+
+            // Once the receiver has drained the channel, the sender sees it as empty again.
+            done_rcv.dequeue(time).unwrap();
+            let debug = format!("{snd:?}");
+            assert!(debug.contains("occupancy: 0/8"), "{debug}");
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        done_snd.attach_sender(&receiver);
+        receiver.set_run(move |time| {
+            rcv.dequeue(time).unwrap();
+            let debug = format!("{rcv:?}");
+            assert!(debug.contains("received: 1"), "{debug}");
+            for _ in 1..8 {
+                rcv.dequeue(time).unwrap();
+            }
+            done_snd
+                .enqueue(time, ChannelElement::new(time.tick() + 1, true))
+                .unwrap();
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_monitor_occupancy() {
+        const CAPACITY: usize = 4;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(CAPACITY);
+        let (done_snd, done_rcv) = ctx.bounded(1);
+
+        let crossings = Arc::new(Mutex::new(vec![]));
+        let recorder = |name: &'static str| {
+            let crossings = crossings.clone();
+            Box::new(move |occupancy: f64| crossings.lock().unwrap().push((name, occupancy)))
+        };
+        snd.monitor_occupancy(0.5, recorder("warning"));
+        snd.monitor_occupancy(0.95, recorder("critical"));
+        let id = snd.monitor_occupancy(0.0, recorder("removed"));
+        assert!(snd.remove_monitor(id));
+        assert!(!snd.remove_monitor(id));
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        done_rcv.attach_receiver(&sender);
+        sender.set_run(move |time| {
+            // Fill the channel, which crosses both thresholds on the way up.
+            for iter in 0..CAPACITY {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+            }
+            // Wait for the receiver to drain it, so that the next send sees an occupancy of a quarter and re-arms both monitors.
+            done_rcv.dequeue(time).unwrap();
+            // Only the warning threshold is crossed again.
+            for iter in CAPACITY..CAPACITY + 3 {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        done_snd.attach_sender(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 0..CAPACITY {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+            }
+            done_snd
+                .enqueue(time, ChannelElement::new(time.tick() + 1, true))
+                .unwrap();
+            while rcv.dequeue(time).is_ok() {}
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+        assert_eq!(
+            *crossings.lock().unwrap(),
+            vec![("warning", 0.75), ("critical", 1.0), ("warning", 0.75)]
+        );
+    }
+
+    #[test]
+    fn test_context_timeout() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+
+        let mut driver = FunctionContext::default();
+        snd.attach_sender(&driver);
+        driver.set_run(move |time| {
+            // This would run forever, were it not for the budget.
+            while snd
+                .enqueue(
+                    time,
+                    ChannelElement::new(time.tick() + 1, time.tick().time()),
+                )
+                .is_ok()
+            {
+                time.incr_cycles(1);
+            }
+            assert_eq!(time.tick(), Time::new(11));
+        });
+        let driver_id = driver.id();
+        ctx.add_child(driver);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            let mut received = 0;
+            while rcv.dequeue(time).is_ok() {
+                received += 1;
+            }
+            // Elements were sent at ticks 0 through 10.
+            assert_eq!(received, 11);
+        });
+        ctx.add_child(receiver);
+
+        ctx.with_context_timeout(driver_id, 10).unwrap();
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    /// Round-trips a value through JSON, BSON, bincode, and MessagePack, returning each result.
+    #[cfg(feature = "serialize")]
+    fn round_trip<T>(value: &T) -> [T; 4]
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        [
+            serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap(),
+            bson::from_bson(bson::to_bson(value).unwrap()).unwrap(),
+            bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap(),
+            rmp_serde::from_slice(&rmp_serde::to_vec(value).unwrap()).unwrap(),
+        ]
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")]
+    fn test_serde_round_trip() {
+        use dam::channel::Recv;
+
+        let element = ChannelElement::new(Time::new(42), vec![1u32, 2, 3]);
+        assert!(round_trip(&element).iter().all(|result| *result == element));
+
+        let id = ChannelID::new();
+        assert!(round_trip(&id).iter().all(|result| *result == id));
+
+        for options in [
+            SendOptions::AvailableAt(Time::new(5)),
+            SendOptions::CheckBackAt(Time::new(3)),
+        ] {
+            assert!(round_trip(&options).iter().all(|result| *result == options));
+        }
+
+        for recv in [
+            Recv::Valid(ChannelElement::new(Time::new(1), 2u64)),
+            Recv::Corrupt(ChannelElement::new(Time::new(3), 4u64)),
+        ] {
+            assert!(round_trip(&recv).iter().all(|result| *result == recv));
+        }
+
+        let something = PeekResult::Something(element.clone());
+        assert!(round_trip(&something)
+            .iter()
+            .all(|result| matches!(result, PeekResult::Something(head) if *head == element)));
+        let nothing: PeekResult<u32> = PeekResult::Nothing(Time::new(7));
+        assert!(round_trip(&nothing)
+            .iter()
+            .all(|result| matches!(result, PeekResult::Nothing(time) if *time == Time::new(7))));
+        let closed: PeekResult<u32> = PeekResult::Closed;
+        assert!(round_trip(&closed)
+            .iter()
+            .all(|result| matches!(result, PeekResult::Closed)));
+    }
+
+    #[test]
+    fn test_fold_and_scan() {
+        let mut ctx = ProgramBuilder::default();
+        // The receiver drains one channel before the other, so these need to be unbounded.
+        let (snd, rcv) = ctx.unbounded();
+        let (scan_snd, scan_rcv) = ctx.unbounded();
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        scan_snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 1..=10u64 {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                scan_snd
+                    .enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                time.incr_cycles(1);
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        scan_rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            let prefix_sums: Vec<_> = scan_rcv
+                .scan(0, |acc, element| acc + element.data, time)
+                .collect();
+            assert_eq!(prefix_sums, vec![1, 3, 6, 10, 15, 21, 28, 36, 45, 55]);
+
+            let sum = rcv.fold(0, |acc, element| acc + element.data, time);
+            assert_eq!(sum.unwrap(), 55);
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_recv_with_transform() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(2);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 1..=10u64 {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                time.incr_cycles(1);
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 1..=10u64 {
+                // The data is transformed, but the timestamp is kept.
+                let element = rcv
+                    .recv_with_transform(time, |data| format!("#{data}"))
+                    .unwrap();
+                assert_eq!(element.data, format!("#{iter}"));
+                assert_eq!(element.time, Time::new(iter));
+            }
+            // The alias behaves the same.
+            assert!(rcv.dequeue_map(time, |data| data).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_into_iter_simulation() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            snd.enqueue_iter(
+                time,
+                (1..=200u64).map(|iter| ChannelElement::new(Time::new(iter), iter)),
+            )
+            .unwrap();
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            let late = rcv
+                .into_iter_simulation(time)
+                .filter(|element| element.time > Time::new(100))
+                .count();
+            assert_eq!(late, 100);
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_enqueue_iter() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            snd.enqueue_iter(
+                time,
+                (0..1000u64).map(|iter| ChannelElement::new(Time::new(iter), iter)),
+            )
+            .unwrap();
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 0..1000 {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+                time.incr_cycles(1);
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_close_on_tick() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            snd.close_on_tick(Time::new(100));
+            let sent = (1..1000u64)
+                .take_while(|iter| {
+                    snd.enqueue(time, ChannelElement::new(Time::new(*iter), *iter))
+                        .is_ok()
+                })
+                .count();
+            assert_eq!(sent, 100);
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 1..=100 {
+                let element = rcv.dequeue(time).unwrap();
+                assert_eq!(element.data, iter);
+                assert_eq!(element.time, Time::new(iter));
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_skip_until() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            snd.enqueue_iter(
+                time,
+                (1..=20u64).map(|iter| ChannelElement::new(Time::new(iter), iter)),
+            )
+            .unwrap();
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            assert_eq!(rcv.skip_until(Time::new(10), time), 9);
+            assert!(time.tick() <= Time::new(10));
+            // Nothing left to skip, so this is a no-op.
+            assert_eq!(rcv.skip_until(Time::new(10), time), 0);
+            let element = rcv.dequeue(time).unwrap();
+            assert_eq!(element.time, Time::new(10));
+            assert_eq!(element.data, 10);
+            assert_eq!(rcv.skip_until(Time::new(100), time), 10);
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_send_after_close() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(2);
+        let closed = Arc::new(AtomicU64::new(0));
+        let closed_count = closed.clone();
+        snd.add_observer(Box::new(move |event| {
+            if let ChannelEvent::Closed { .. } = event {
+                closed_count.fetch_add(1, Ordering::Relaxed);
+            }
+        }));
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            snd.enqueue(time, ChannelElement::new(time.tick(), 0))
+                .unwrap();
+            snd.close();
+            assert!(snd.is_closed());
+            // Closing again does nothing, and sending is an error rather than a panic.
+            snd.close();
+            assert!(snd
+                .enqueue(time, ChannelElement::new(time.tick(), 1))
+                .is_err());
+            assert!(snd
+                .enqueue_iter(
+                    time,
+                    (2..4).map(|iter| ChannelElement::new(time.tick(), iter))
+                )
+                .is_err());
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            assert_eq!(rcv.dequeue(time).unwrap().data, 0);
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+        assert_eq!(closed.load(Ordering::Relaxed), 1);
+    }
+
+    fn run_observers_test(explicit_close: bool) {
+        use std::sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        };
+
+        const NUM_ELEMENTS: u64 = 100;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(2);
+
+        let sent = Arc::new(AtomicU64::new(0));
+        let received = Arc::new(AtomicU64::new(0));
+        let full = Arc::new(AtomicU64::new(0));
+        let empty = Arc::new(AtomicU64::new(0));
+        let closed = Arc::new(AtomicU64::new(0));
+        {
+            let (sent, received, full, empty, closed) = (
+                sent.clone(),
+                received.clone(),
+                full.clone(),
+                empty.clone(),
+                closed.clone(),
+            );
+            let id = snd.id();
+            snd.add_observer(Box::new(move |event| match event {
+                ChannelEvent::Sent { id: event_id, .. } => {
+                    assert_eq!(event_id, id);
+                    sent.fetch_add(1, Ordering::Relaxed);
+                }
+                ChannelEvent::Received { .. } => {
+                    received.fetch_add(1, Ordering::Relaxed);
+                }
+                ChannelEvent::Full { .. } => {
+                    full.fetch_add(1, Ordering::Relaxed);
+                }
+                ChannelEvent::Empty { .. } => {
+                    empty.fetch_add(1, Ordering::Relaxed);
+                }
+                ChannelEvent::Closed { .. } => {
+                    closed.fetch_add(1, Ordering::Relaxed);
+                }
+            }));
+        }
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                snd.enqueue(time, ChannelElement::new(time.tick(), iter))
+                    .unwrap();
+                // The second half is sent slower than the receiver drains it.
+                if iter >= NUM_ELEMENTS / 2 {
+                    time.incr_cycles(4);
+                }
+            }
+            if explicit_close {
+                snd.close();
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            // Starting late lets the sender fill the channel.
+            time.incr_cycles(10);
+            while rcv.dequeue(time).is_ok() {
+                time.incr_cycles(1);
+            }
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+        assert_eq!(sent.load(Ordering::Relaxed), NUM_ELEMENTS);
+        assert_eq!(received.load(Ordering::Relaxed), NUM_ELEMENTS);
+        assert!(full.load(Ordering::Relaxed) > 0);
+        assert!(empty.load(Ordering::Relaxed) > 0);
+        assert_eq!(closed.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_observers() {
+        run_observers_test(true);
+    }
+
+    #[test]
+    fn test_observers_close_on_drop() {
+        run_observers_test(false);
+    }
+
+    #[test]
+    fn test_take_head() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(2);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..5 {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            // Nothing has been fetched yet, so there is no head to take.
+            let (head, rcv) = rcv.take_head();
+            assert!(head.is_none());
+
+            assert_eq!(rcv.peek_next(time).unwrap().data, 0);
+            let (head, rcv) = rcv.take_head();
+            assert_eq!(head.unwrap().data, 0);
+            let (head, rcv) = rcv.take_head();
+            assert!(head.is_none());
+
+            for iter in 1..5 {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_stall_histogram() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(1);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..5 {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+            }
+            let histogram = snd.stall_histogram();
+            assert_eq!(histogram.num_stalls(), 4);
+            assert!(histogram.percentile(100.0) >= 10);
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for _ in 0..5 {
+                rcv.dequeue(time).unwrap();
+                time.incr_cycles(10);
+            }
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_latency_adapters() {
+        const LATENCY: u64 = 5;
+        let mut ctx = ProgramBuilder::default();
+        let (delayed_snd, delayed_rcv) = ctx.unbounded();
+        let (symmetric_snd, symmetric_rcv) = ctx.unbounded();
+        let delayed_snd = delayed_snd.with_latency(LATENCY);
+        let symmetric_snd = symmetric_snd.with_latency(LATENCY);
+        let symmetric_rcv = symmetric_rcv.with_latency(LATENCY);
+
+        let mut sender = FunctionContext::default();
+        delayed_snd.attach_sender(&sender);
+        symmetric_snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..8u64 {
+                let element = ChannelElement::new(time.tick() + 1, iter);
+                delayed_snd.enqueue(time, element.clone()).unwrap();
+                symmetric_snd.enqueue(time, element).unwrap();
+                time.incr_cycles(1);
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        delayed_rcv.attach_receiver(&receiver);
+        symmetric_rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 0..8u64 {
+                let delayed = delayed_rcv.dequeue(time).unwrap();
+                assert_eq!(delayed.time.time(), iter + 1 + LATENCY);
+
+                let symmetric = symmetric_rcv.dequeue(time).unwrap();
+                assert_eq!(symmetric.time.time(), iter + 1);
+            }
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_jittered_sender() {
+        const MAX_JITTER: u64 = 8;
+        const NUM_ELEMENTS: u64 = 8000;
+        const SEED: u64 = 0x5eed;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+        let snd = snd.with_jitter(MAX_JITTER, fastrand::Rng::with_seed(SEED));
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                time.incr_cycles(1);
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            // The same seed produces the same delays.
+            let mut expected = fastrand::Rng::with_seed(SEED);
+            let mut histogram = [0u64; MAX_JITTER as usize];
+            let mut last = Time::new(0);
+            for iter in 0..NUM_ELEMENTS {
+                let element = rcv.dequeue(time).unwrap();
+                assert_eq!(element.data, iter);
+                let jitter = expected.u64(0..MAX_JITTER);
+                // Timestamps never decrease, even when a delay is shorter than the previous one.
+                assert!(element.time >= last);
+                assert_eq!(element.time, last.max(Time::new(iter + 1 + jitter)));
+                last = element.time;
+                histogram[jitter as usize] += 1;
+            }
+            let mean = NUM_ELEMENTS / MAX_JITTER;
+            for count in histogram {
+                assert!(count.abs_diff(mean) < mean / 5, "{histogram:?}");
+            }
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_flow_controlled_sender() {
+        const MAX_AHEAD: u64 = 5;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+        let (go_snd, go_rcv) = ctx.bounded(1);
+        let snd = snd.with_flow_control(MAX_AHEAD);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        go_snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            // The receiver is held at tick 0 until it gets the go signal, so the sender may only run MAX_AHEAD ticks ahead.
+            time.incr_cycles(MAX_AHEAD);
+            assert_eq!(
+                snd.try_enqueue(time, ChannelElement::new(time.tick() + 1, 0u64)),
+                Ok(())
+            );
+            time.incr_cycles(1);
+            assert_eq!(
+                snd.try_enqueue(time, ChannelElement::new(time.tick() + 1, 1u64)),
+                Err(SendOptions::CheckBackAt(Time::new(1)))
+            );
+
+            // Once the receiver catches up, sending is allowed again.
+            go_snd
+                .enqueue(time, ChannelElement::new(time.tick() + 1, ()))
+                .unwrap();
+            snd.enqueue(time, ChannelElement::new(time.tick() + 1, 1u64))
+                .unwrap();
+            assert_eq!(
+                snd.try_enqueue(time, ChannelElement::new(time.tick() + 1, 2u64)),
+                Ok(())
+            );
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        go_rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            go_rcv.dequeue(time).unwrap();
+            for iter in 0..3u64 {
+                assert_eq!(rcv.dequeue(time).unwrap().data, iter);
+            }
+            assert!(rcv.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_shared_sender() {
+        const WORKERS: u64 = 2;
+        const PER_WORKER: u64 = 500;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+        let snd = snd.shared();
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            let time = &*time;
+            std::thread::scope(|scope| {
+                for worker in 0..WORKERS {
+                    let snd = snd.clone();
+                    scope.spawn(move || {
+                        for iter in 0..PER_WORKER {
+                            snd.enqueue(
+                                time,
+                                ChannelElement::new(time.tick() + 1, worker * PER_WORKER + iter),
+                            )
+                            .unwrap();
+                        }
+                    });
+                }
+            });
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            let mut received = vec![vec![]; WORKERS as usize];
+            while let Ok(element) = rcv.dequeue(time) {
+                received[(element.data / PER_WORKER) as usize].push(element.data % PER_WORKER);
+            }
+            // Sends from different workers interleave, but each worker's sends stay in order.
+            for values in received {
+                assert_eq!(values, (0..PER_WORKER).collect::<Vec<_>>());
+            }
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_shared_sender_backpressure() {
+        const CAPACITY: usize = 4;
+        // Each clone alone fits in the channel, but together they overflow it.
+        const PER_WORKER: u64 = CAPACITY as u64 / 2 + 1;
+        const RECEIVER_DELAY: u64 = 100;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(CAPACITY);
+        let snd = snd.shared();
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            assert_eq!(snd.peek_remaining_capacity(), CAPACITY);
+            let time = &*time;
+            std::thread::scope(|scope| {
+                for worker in 0..2 {
+                    let snd = snd.clone();
+                    scope.spawn(move || {
+                        for iter in 0..PER_WORKER {
+                            snd.enqueue(
+                                time,
+                                ChannelElement::new(Time::new(1), worker * PER_WORKER + iter),
+                            )
+                            .unwrap();
+                        }
+                    });
+                }
+            });
+            // The last sends had to wait for the receiver to free up space.
+            assert!(time.tick() > Time::new(RECEIVER_DELAY));
+            snd.close();
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            time.incr_cycles(RECEIVER_DELAY);
+            let mut received = 0;
+            while rcv.dequeue(time).is_ok() {
+                received += 1;
+                time.incr_cycles(1);
+            }
+            assert_eq!(received, 2 * PER_WORKER);
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_shared_receiver_peek_handle() {
+        const NUM_ELEMENTS: u64 = 16;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                snd.enqueue(time, ChannelElement::new(Time::new(iter + 1), iter))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let rcv = rcv.shared();
+        let monitor = rcv.peek_only_handle();
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                let head = rcv.peek_next(time).unwrap();
+                // The monitor sees the same head as the consumer, without taking it.
+                match monitor.peek() {
+                    PeekResult::Something(element) => assert_eq!(element, head),
+                    _ => panic!("Monitor did not see the head of the channel"),
+                }
+                let element = rcv.dequeue(time).unwrap();
+                assert_eq!(element, head);
+                assert_eq!(element.data, iter);
+            }
+            assert!(rcv.dequeue(time).is_err());
+            assert!(matches!(monitor.peek(), PeekResult::Closed));
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_element_display() {
+        assert_eq!(
+            ChannelElement::new(Time::new(42), "hello").to_string(),
+            "@tick 42: hello"
+        );
+        let elems = [
+            ChannelElement::new(Time::new(3), 'a'),
+            ChannelElement::new(Time::new(12345), 'b'),
+        ];
+        assert_eq!(
+            ChannelElement::display_table(&elems),
+            " tick | data\n    3 | a\n12345 | b"
+        );
+    }
+
+    #[test]
+    fn test_element_update_time() {
+        let mut element = ChannelElement::new(Time::new(10), ());
+        element.update_time(Time::new(5));
+        assert_eq!(element.time, Time::new(10));
+        element.update_time_if_earlier(Time::new(5));
+        assert_eq!(element.time, Time::new(5));
+        element.update_time_if_earlier(Time::infinite());
+        assert_eq!(element.time, Time::new(5));
+
+        element.update_time(Time::infinite());
+        assert!(element.time.is_infinite());
+        element.update_time(Time::new(20));
+        assert!(element.time.is_infinite());
+        element.update_time_if_earlier(Time::new(20));
+        assert_eq!(element.time, Time::new(20));
+
+        assert_eq!(element.with_time(Time::new(3)).time, Time::new(3));
+    }
+
+    #[test]
+    fn test_element_normalize() {
+        let element = ChannelElement::new(Time::new(15), ());
+        assert_eq!(
+            element.clone().normalize_time(Time::new(10)).time,
+            Time::new(5)
+        );
+        assert_eq!(element.normalize_time(Time::new(20)).time, Time::new(0));
+        assert!(ChannelElement::new(Time::infinite(), ())
+            .normalize_time(Time::new(10))
+            .time
+            .is_infinite());
+
+        let mut batch = [12, 10, 17].map(|time| ChannelElement::new(Time::new(time), ()));
+        ChannelElement::normalize_batch(&mut batch);
+        assert_eq!(batch.map(|elem| elem.time.time()), [2, 0, 7]);
+    }
+
+    #[test]
+    fn test_element_hash() {
+        let elements: std::collections::HashSet<_> = [
+            ChannelElement::new(Time::new(1), 'a'),
+            ChannelElement::new(Time::new(1), 'a'),
+            ChannelElement::new(Time::new(2), 'a'),
+            ChannelElement::new(Time::from((true, 3)), 'b'),
+            ChannelElement::new(Time::infinite(), 'b'),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(elements.len(), 3);
+        assert!(elements.contains(&ChannelElement::new(Time::new(2), 'a')));
+        assert!(elements.contains(&ChannelElement::new(Time::from((true, 7)), 'b')));
+    }
+
+    #[test]
+    fn test_filter_map() {
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..32u32 {
+                snd.enqueue(time, ChannelElement::new(Time::new(iter as u64 + 1), iter))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let halves = rcv.filter_map(|x: u32| (x % 2 == 0).then_some(x as f64 / 2.0));
+        let mut receiver = FunctionContext::default();
+        halves.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            for iter in 0..16u32 {
+                let peeked = halves.peek_next(time).unwrap();
+                let element = halves.dequeue(time).unwrap();
+                assert_eq!(peeked.data, element.data);
+                assert_eq!(element.data, iter as f64);
+                assert_eq!(element.time, Time::new(iter as u64 * 2 + 1));
+            }
+            assert!(halves.dequeue(time).is_err());
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_filter_map_peek() {
+        const NUM_ELEMENTS: u32 = 32;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(4);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                snd.enqueue(time, ChannelElement::new(Time::new(iter as u64 + 1), iter))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(sender);
+
+        let halves = rcv.filter_map(|x: u32| (x % 2 == 0).then_some(x as f64 / 2.0));
+        let mut receiver = FunctionContext::default();
+        halves.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            // Polls one cycle at a time, so that the skipped elements sit at the head when peeked.
+            let mut received = 0u32;
+            loop {
+                assert!(time.tick() <= Time::new(NUM_ELEMENTS as u64 + 1));
+                match halves.peek() {
+                    PeekResult::Something(element) => {
+                        assert_eq!(element.data, received as f64);
+                        assert_eq!(element.time, Time::new(received as u64 * 2 + 1));
+                        assert_eq!(halves.dequeue(time).unwrap(), element);
+                        received += 1;
+                    }
+                    PeekResult::Nothing(nothing) => {
+                        assert!(nothing >= time.tick());
+                        time.incr_cycles(1);
+                    }
+                    PeekResult::Closed => break,
+                }
+            }
+            assert_eq!(received, NUM_ELEMENTS / 2);
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_zip_with() {
+        const NUM_ELEMENTS: u64 = 16;
+        // The right stream is skewed by up to two cycles, and is one element shorter.
+        let left_time = |iter: u64| iter * 4 + 1;
+        let right_time = |iter: u64| iter * 4 + 1 + iter % 3;
+        let mut ctx = ProgramBuilder::default();
+        let (left_snd, left_rcv) = ctx.bounded(2);
+        let (right_snd, right_rcv) = ctx.bounded(2);
+
+        let mut left = FunctionContext::default();
+        left_snd.attach_sender(&left);
+        left.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS {
+                left_snd
+                    .enqueue(time, ChannelElement::new(Time::new(left_time(iter)), iter))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(left);
+
+        let mut right = FunctionContext::default();
+        right_snd.attach_sender(&right);
+        right.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS - 1 {
+                right_snd
+                    .enqueue(
+                        time,
+                        ChannelElement::new(Time::new(right_time(iter)), -(iter as i32)),
+                    )
+                    .unwrap();
+            }
+        });
+        ctx.add_child(right);
+
+        let mut zipper = FunctionContext::default();
+        left_rcv.attach_receiver(&zipper);
+        right_rcv.attach_receiver(&zipper);
+        zipper.set_run(move |time| {
+            for iter in 0..NUM_ELEMENTS - 1 {
+                let element = left_rcv.zip_with(&right_rcv, time).unwrap();
+                assert_eq!(element.data, (iter, -(iter as i32)));
+                assert_eq!(element.time, Time::new(right_time(iter)));
+                assert_eq!(time.tick(), element.time);
+            }
+            assert!(left_rcv.zip_with(&right_rcv, time).is_err());
+            // The unpaired element is left in the channel.
+            assert_eq!(left_rcv.dequeue(time).unwrap().data, NUM_ELEMENTS - 1);
+        });
+        ctx.add_child(zipper);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_count_until_closed() {
+        const NUM_ELEMENTS: u64 = 1000;
+        let mut ctx = ProgramBuilder::default();
+        let mut receivers = vec![];
+        for _ in 0..3 {
+            let (snd, rcv) = ctx.bounded(8);
+            receivers.push(rcv);
+            let mut sender = FunctionContext::default();
+            snd.attach_sender(&sender);
+            sender.set_run(move |time| {
+                for iter in 0..NUM_ELEMENTS {
+                    snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                        .unwrap();
+                    time.incr_cycles(1);
+                }
+            });
+            ctx.add_child(sender);
+        }
+
+        let mut receiver = FunctionContext::default();
+        receivers
+            .iter()
+            .for_each(|rcv| rcv.attach_receiver(&receiver));
+        receiver.set_run(move |time| {
+            assert_eq!(receivers[0].count_until_closed(time), NUM_ELEMENTS);
+            let count = receivers[1]
+                .count_until_closed_with_timeout(time, Duration::from_secs(600))
+                .unwrap();
+            assert_eq!(count, NUM_ELEMENTS);
+            let timeout = receivers[2]
+                .count_until_closed_with_timeout(time, Duration::ZERO)
+                .unwrap_err();
+            assert_eq!(timeout.count, 0);
+            assert_eq!(receivers[2].count_until_closed(time), NUM_ELEMENTS);
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_count_until_closed_stalled_sender() {
+        const BEFORE_STALL: u64 = 3;
+        const AFTER_STALL: u64 = 2;
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.bounded(8);
+
+        let mut sender = FunctionContext::default();
+        snd.attach_sender(&sender);
+        sender.set_run(move |time| {
+            for iter in 0..BEFORE_STALL + AFTER_STALL {
+                if iter == BEFORE_STALL {
+                    // Stalls in wall-clock time without advancing simulated time.
+                    dam::shim::sleep(Duration::from_millis(500));
+                }
+                snd.enqueue(time, ChannelElement::new(time.tick() + 1, iter))
+                    .unwrap();
+                time.incr_cycles(1);
+            }
+        });
+        ctx.add_child(sender);
+
+        let mut receiver = FunctionContext::default();
+        rcv.attach_receiver(&receiver);
+        receiver.set_run(move |time| {
+            let timeout = rcv
+                .count_until_closed_with_timeout(time, Duration::from_millis(50))
+                .unwrap_err();
+            assert_eq!(timeout.count, BEFORE_STALL);
+            assert_eq!(rcv.count_until_closed(time), AFTER_STALL);
+        });
+        ctx.add_child(receiver);
+
+        let executed = ctx
+            .initialize(InitializationOptionsBuilder::default().build().unwrap())
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
+    #[test]
+    fn test_timeout_policy_detects_deadlock() {
+        use dam::structures::TimeoutPolicy;
+
+        let mut ctx = ProgramBuilder::default();
+        let (a_snd, a_rcv) = ctx.bounded::<u32>(1);
+        let (b_snd, b_rcv) = ctx.bounded::<u32>(1);
+        let messages = Arc::new(Mutex::new(vec![]));
+
+        // Each context waits on the other before sending anything, so neither can make progress.
+        for (snd, rcv) in [(a_snd, b_rcv), (b_snd, a_rcv)] {
+            let mut node = FunctionContext::default();
+            snd.attach_sender(&node);
+            rcv.attach_receiver(&node);
+            let messages = messages.clone();
+            node.set_run(move |time| {
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    let element = rcv.dequeue(time).unwrap();
+                    snd.enqueue(time, element).unwrap();
+                }));
+                let payload = result.unwrap_err();
+                let message = payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .unwrap_or_default();
+                messages.lock().unwrap().push(message);
+                // Finishing would release the other context, so stay put until it has timed out too.
+                while messages.lock().unwrap().len() < 2 {
+                    dam::shim::sleep(Duration::from_millis(1));
+                }
+            });
+            ctx.add_child(node);
+        }
+
+        let executed = ctx
+            .initialize(
+                InitializationOptionsBuilder::default()
+                    .timeout_policy(TimeoutPolicy::PanicAfter(Duration::from_millis(100)))
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+        let messages = messages.lock().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages
+            .iter()
+            .all(|message| message.contains("Timed out after 100ms waiting for the sender")));
+    }
+
+    #[test]
+    fn test_initial_tokens_feedback_loop() {
+        const ITERS: u64 = 16;
+        let mut ctx = ProgramBuilder::default();
+        let (forward_snd, forward_rcv) = ctx.bounded(2);
+        let (feedback_snd, feedback_rcv) = ctx.bounded(2);
+
+        assert!(ctx
+            .inject_initial_tokens(
+                &feedback_snd,
+                vec![ChannelElement::new(Time::new(0), 0u64); 3]
+            )
+            .is_err());
+        ctx.inject_initial_tokens(&feedback_snd, vec![ChannelElement::new(Time::new(0), 0u64)])
+            .unwrap();
+
+        let mut head = FunctionContext::default();
+        forward_snd.attach_sender(&head);
+        feedback_rcv.attach_receiver(&head);
+        head.set_run(move |time| {
+            for iter in 0..ITERS {
+                let token = feedback_rcv.dequeue(time).unwrap();
+                assert_eq!(token.data, iter);
+                forward_snd
+                    .enqueue(time, ChannelElement::new(time.tick() + 1, token.data + 1))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(head);
+
+        let mut tail = FunctionContext::default();
+        forward_rcv.attach_receiver(&tail);
+        feedback_snd.attach_sender(&tail);
+        tail.set_run(move |time| {
+            for _ in 0..ITERS {
+                let token = forward_rcv.dequeue(time).unwrap();
+                feedback_snd
+                    .enqueue(time, ChannelElement::new(time.tick() + 1, token.data))
+                    .unwrap();
+            }
+        });
+        ctx.add_child(tail);
+
+        let executed = ctx
+            .initialize(
+                InitializationOptionsBuilder::default()
+                    .run_flavor_inference(true)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap()
+            .run(RunOptions::default());
+        assert!(executed.passed());
+    }
+
     fn run_channel_test(test_size: i32, flavor_inference: bool, capacity: Option<usize>) {
         let mut ctx = ProgramBuilder::default();
 